@@ -0,0 +1,209 @@
+use remote::DEFAULT_REMOTE;
+use sliderule::SROutput;
+use std::path::Path;
+use tools;
+use worktree;
+
+/*
+ * The branch that git starts new repositories on when it has not been told to use another one.
+ */
+const FALLBACK_BRANCH: &str = "master";
+
+/*
+ * Gets the branch that Sliderule uploads to and downloads from when no other branch is in use. This
+ * is the default branch of the component's remote repository when it is known, such as after the
+ * component was downloaded, or else the branch that git starts new repositories on.
+ */
+pub fn get_default(component_path: &Path) -> String {
+    let remote_head = format!("refs/remotes/{}/HEAD", DEFAULT_REMOTE);
+
+    // The remote's default branch is recorded as origin/main or similar
+    if let Ok(head) = tools::git(component_path, &["symbolic-ref", "--short", &remote_head]) {
+        if let Some(name) = head.trim().split_once('/').map(|parts| parts.1) {
+            if !name.is_empty() {
+                return name.to_string();
+            }
+        }
+    }
+
+    match tools::git(component_path, &["config", "init.defaultBranch"]) {
+        Ok(ref name) if !name.trim().is_empty() => name.trim().to_string(),
+        _ => String::from(FALLBACK_BRANCH),
+    }
+}
+
+/*
+ * Gets the name of the branch a component is on, or None if the component has no repository yet.
+ */
+pub fn current(component_path: &Path) -> Option<String> {
+    match tools::git(component_path, &["rev-parse", "--abbrev-ref", "HEAD"]) {
+        Ok(ref branch) if !branch.trim().is_empty() && branch.trim() != "HEAD" => {
+            Some(branch.trim().to_string())
+        }
+        _ => None,
+    }
+}
+
+/*
+ * Lists the branches of a component, including the ones that have only been uploaded by others.
+ * Each branch is paired with whether it only exists in the remote repository.
+ */
+pub fn list(component_path: &Path) -> Result<Vec<(String, bool)>, String> {
+    let local = tools::git(component_path, &["branch", "--format=%(refname:short)"])?;
+    let remote = tools::git(
+        component_path,
+        &["branch", "-r", "--format=%(refname:short)"],
+    )
+    .unwrap_or_default();
+
+    let mut branches: Vec<(String, bool)> = local
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| (line.trim().to_string(), false))
+        .collect();
+
+    for line in remote.lines() {
+        // Remote branches are listed as origin/name, along with git's pointer to the default branch
+        let name = match line.trim().split_once('/').map(|parts| parts.1) {
+            Some(name) if name != "HEAD" && !name.is_empty() => name.to_string(),
+            _ => continue,
+        };
+
+        if !branches.iter().any(|branch| branch.0 == name) {
+            branches.push((name, true));
+        }
+    }
+
+    Ok(branches)
+}
+
+/*
+ * Creates a new branch from the current state of a component and switches to it.
+ */
+pub fn create(component_path: &Path, name: &str) -> Result<(), String> {
+    tools::git(component_path, &["checkout", "-b", name]).map(|_| ())
+}
+
+/*
+ * Switches a component to another branch. Changes that have not been uploaded would otherwise be
+ * carried over to the other branch, so they have to be dealt with first.
+ */
+pub fn switch(component_path: &Path, name: &str) -> Result<(), String> {
+    if !worktree::list_uncommitted(component_path).is_empty() {
+        return Err(String::from(
+            "This component has changes that have not been uploaded. Upload them before switching branches.",
+        ));
+    }
+
+    // Branches that have only been uploaded by others are set up to track the remote one
+    tools::git(component_path, &["checkout", name]).map(|_| ())
+}
+
+/*
+ * Merges the changes from another branch into the current one.
+ */
+pub fn merge(component_path: &Path, name: &str) -> Result<(), String> {
+    if !worktree::list_uncommitted(component_path).is_empty() {
+        return Err(String::from(
+            "This component has changes that have not been uploaded. Upload them before merging.",
+        ));
+    }
+
+    tools::git(component_path, &["merge", "--no-edit", name]).map(|_| ())
+}
+
+/*
+ * Deletes a branch. Branches with changes that have not been merged are only deleted when forced.
+ */
+pub fn delete(component_path: &Path, name: &str, force: bool) -> Result<(), String> {
+    if current(component_path).as_deref() == Some(name) {
+        return Err(format!(
+            "{} is the current branch. Switch to another branch before deleting it.",
+            name
+        ));
+    }
+
+    let flag = if force { "-D" } else { "-d" };
+
+    tools::git(component_path, &["branch", flag, name]).map(|_| ())
+}
+
+/*
+ * Uploads the changes to a component to the branch it is on, in the given remote.
+ */
+pub fn upload(component_path: &Path, remote: &str, branch: &str, message: &str) -> SROutput {
+    let mut stdout: Vec<String> = Vec::new();
+
+    if let Err(e) = tools::git(component_path, &["add", "-A"]) {
+        return failure(stdout, e);
+    }
+
+    // There may only be commits left over from an earlier upload that could not be pushed
+    if !worktree::list_uncommitted(component_path).is_empty() {
+        match tools::git(component_path, &["commit", "-m", message]) {
+            Ok(out) => stdout.push(out),
+            Err(e) => return failure(stdout, e),
+        }
+    }
+
+    // Only the default remote is tracked, so that other remotes are only used when they are named
+    let mut args = vec!["push"];
+    if remote == DEFAULT_REMOTE {
+        args.push("-u");
+    }
+    args.extend(&[remote, branch]);
+
+    match tools::git(component_path, &args) {
+        Ok(out) => stdout.push(out),
+        Err(e) => return failure(stdout, e),
+    }
+
+    success(stdout)
+}
+
+/*
+ * Downloads the changes to the branch a component is on from the given remote.
+ */
+pub fn download(component_path: &Path, remote: &str, branch: &str) -> SROutput {
+    // Nobody can have uploaded changes to a branch that has never been uploaded
+    match tools::git(component_path, &["ls-remote", "--heads", remote, branch]) {
+        Ok(ref heads) if heads.trim().is_empty() => {
+            return success(vec![format!(
+                "The branch {} has not been uploaded to {} yet, so there are no changes to download.",
+                branch, remote
+            )]);
+        }
+        Ok(_) => {}
+        Err(e) => return failure(Vec::new(), e),
+    }
+
+    match tools::git(component_path, &["pull", remote, branch]) {
+        Ok(out) => success(vec![out]),
+        Err(e) => failure(Vec::new(), e),
+    }
+}
+
+/*
+ * Makes the output of a git operation that succeeded.
+ */
+fn success(stdout: Vec<String>) -> SROutput {
+    SROutput {
+        status: 0,
+        wrapped_status: 0,
+        stdout,
+        stderr: Vec::new(),
+    }
+}
+
+/*
+ * Makes the output of a git operation that failed, in the same way that Sliderule reports a failed
+ * git or npm process.
+ */
+fn failure(stdout: Vec<String>, error: String) -> SROutput {
+    SROutput {
+        status: 0,
+        wrapped_status: 1,
+        stdout,
+        stderr: vec![error],
+    }
+}
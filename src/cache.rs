@@ -0,0 +1,184 @@
+use component;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tools;
+
+/*
+ * Gets the directory that fetched components are cached in. SLIDERULE_CACHE_DIR overrides the default.
+ */
+pub fn get_cache_dir() -> PathBuf {
+    if let Ok(dir) = env::var("SLIDERULE_CACHE_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    if cfg!(windows) {
+        let local_data = env::var("LOCALAPPDATA").unwrap_or_else(|_| String::from("."));
+
+        PathBuf::from(local_data).join("sliderule").join("cache")
+    } else if let Ok(dir) = env::var("XDG_CACHE_HOME") {
+        PathBuf::from(dir).join("sliderule")
+    } else {
+        let home = env::var("HOME").unwrap_or_else(|_| String::from("."));
+
+        PathBuf::from(home).join(".cache").join("sliderule")
+    }
+}
+
+/*
+ * Points npm at the cache while components are being fetched, and in offline mode redirects git to
+ * the cached copies of components so that the URLs in package.json files can stay the same.
+ * Commands that do not fetch components leave the user's npm cache alone.
+ */
+pub fn configure(offline: bool, fetching: bool) {
+    if !offline && !fetching {
+        return;
+    }
+
+    env::set_var("npm_config_cache", get_cache_dir().join("npm"));
+
+    if !offline {
+        return;
+    }
+
+    env::set_var("npm_config_offline", "true");
+
+    // Add to any git configuration the user has already passed through the environment
+    let mut count = env::var("GIT_CONFIG_COUNT")
+        .ok()
+        .and_then(|count| count.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    for (url, mirror) in list_mirrors() {
+        env::set_var(
+            format!("GIT_CONFIG_KEY_{}", count),
+            format!("url.{}.insteadOf", mirror.display()),
+        );
+        env::set_var(format!("GIT_CONFIG_VALUE_{}", count), url);
+
+        count += 1;
+    }
+
+    env::set_var("GIT_CONFIG_COUNT", count.to_string());
+}
+
+/*
+ * Tells whether a copy of the component at the URL is in the cache.
+ */
+pub fn is_cached(url: &str) -> bool {
+    get_mirror_path(url).exists()
+}
+
+/*
+ * Copies the repository at the URL into the cache, or brings the cached copy up to date.
+ */
+pub fn store(url: &str) -> Result<(), String> {
+    let url = component::get_git_url(url);
+    let mirror = get_mirror_path(&url);
+
+    if mirror.exists() {
+        tools::git(
+            &get_cache_dir(),
+            &[
+                "--git-dir",
+                &mirror.to_string_lossy(),
+                "remote",
+                "update",
+                "--prune",
+            ],
+        )?;
+    } else {
+        fs::create_dir_all(mirror.parent().unwrap())
+            .map_err(|e| format!("Could not create the cache directory: {}", e))?;
+
+        tools::git(
+            &get_cache_dir(),
+            &["clone", "--mirror", &url, &mirror.to_string_lossy()],
+        )?;
+    }
+
+    Ok(())
+}
+
+/*
+ * Caches every remote component that the component at the path depends on.
+ */
+pub fn store_dependencies(component_path: &Path) -> Vec<String> {
+    let mut errors: Vec<String> = Vec::new();
+
+    for (name, location) in component::get_dependencies(component_path) {
+        if !is_remote_location(&location) {
+            continue;
+        }
+
+        if let Err(e) = store(&location) {
+            errors.push(format!("Could not cache {}: {}", name, e));
+        }
+    }
+
+    errors
+}
+
+/*
+ * Gets the location in the cache where the repository for a URL is kept.
+ */
+fn get_mirror_path(url: &str) -> PathBuf {
+    let key: String = component::get_git_url(url)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+
+    get_cache_dir().join("git").join(format!("{}.git", key))
+}
+
+/*
+ * Lists the original URL and cache location of every repository in the cache.
+ */
+fn list_mirrors() -> Vec<(String, PathBuf)> {
+    let mut mirrors: Vec<(String, PathBuf)> = Vec::new();
+
+    let entries = match fs::read_dir(get_cache_dir().join("git")) {
+        Ok(entries) => entries,
+        Err(_) => return mirrors,
+    };
+
+    // The URLs are read from each mirror's config file, rather than by running git for every mirror
+    for entry in entries.filter_map(|e| e.ok()) {
+        if let Some(url) = read_origin_url(&entry.path().join("config")) {
+            mirrors.push((url, entry.path()));
+        }
+    }
+
+    mirrors
+}
+
+/*
+ * Reads the URL of the origin remote from a repository's config file.
+ */
+fn read_origin_url(config_file: &Path) -> Option<String> {
+    let contents = fs::read_to_string(config_file).ok()?;
+
+    let mut in_origin = false;
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if line.starts_with('[') {
+            in_origin = line == "[remote \"origin\"]";
+        } else if in_origin {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "url" {
+                    return Some(value.trim().to_string());
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/*
+ * Tells whether a dependency location points to a repository rather than to the local filesystem.
+ */
+fn is_remote_location(location: &str) -> bool {
+    !location.starts_with("file:") && (location.contains("://") || location.starts_with("git@"))
+}
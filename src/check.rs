@@ -0,0 +1,284 @@
+use component;
+use init;
+use sliderule;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/*
+ * The ways that check knows how to repair a problem automatically.
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Fix {
+    AddMissing,
+    SetName,
+    SyncLicenses,
+    InstallDependencies,
+}
+
+/*
+ * A problem found in a component, along with how to repair it if that can be done automatically.
+ */
+pub struct Problem {
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+/*
+ * Validates a component against the Sliderule component structure. Local components are the
+ * ones that live in the components directory of another component.
+ */
+pub fn check_component(component_path: &Path, is_local: bool) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = Vec::new();
+
+    for missing in component::find_missing(component_path) {
+        let kind = if component::FILES.contains(&missing) {
+            "file"
+        } else {
+            "directory"
+        };
+
+        problems.push(Problem {
+            message: format!("The {} {} is missing.", kind, missing),
+            fix: Some(Fix::AddMissing),
+        });
+    }
+
+    check_sr(component_path, &mut problems);
+    check_package(component_path, is_local, &mut problems);
+    check_bom(component_path, &mut problems);
+
+    // Every remote component in package.json should have been installed by npm
+    for (name, _) in component::get_dependencies(component_path) {
+        if !component_path.join("node_modules").join(&name).exists() {
+            problems.push(Problem {
+                message: format!(
+                    "The remote component {} is listed in package.json but is not installed.",
+                    name
+                ),
+                fix: Some(Fix::InstallDependencies),
+            });
+        }
+    }
+
+    problems
+}
+
+/*
+ * Repairs the problems that can be fixed automatically, returning any errors that happen along the way.
+ */
+pub fn fix_component(component_path: &Path, problems: &[Problem]) -> Vec<String> {
+    let mut errors: Vec<String> = Vec::new();
+    let has_fix = |fix: Fix| problems.iter().any(|problem| problem.fix == Some(fix));
+
+    let dir_name = component_path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+
+    // Missing parts are added first so that the other repairs have files to work with
+    if has_fix(Fix::AddMissing) {
+        let (sr_source, sr_doc) = component::get_sr_licenses(component_path);
+        let defaults = sliderule::get_licenses(component_path);
+        let name = component::get_name(component_path).unwrap_or_else(|| dir_name.clone());
+
+        if let Err(e) = init::add_missing(
+            component_path,
+            &name,
+            sr_source.unwrap_or(defaults.0),
+            sr_doc.unwrap_or(defaults.1),
+        ) {
+            errors.push(e);
+        }
+    }
+
+    if has_fix(Fix::SetName) {
+        match component::get_name(component_path) {
+            Some(old_name) => {
+                if let Err(e) = component::set_name(component_path, &old_name, &dir_name) {
+                    errors.push(format!("Could not update the component name: {}", e));
+                }
+            }
+            None => errors.push(String::from(
+                "Add a name entry to package.json by hand, it cannot be added automatically.",
+            )),
+        }
+    }
+
+    if has_fix(Fix::SyncLicenses) {
+        if let (Some(source), Some(doc)) = component::get_sr_licenses(component_path) {
+            let output = sliderule::change_licenses(component_path, source, doc);
+
+            errors.extend(output.stderr.into_iter().filter(|line| !line.is_empty()));
+        }
+    }
+
+    if has_fix(Fix::InstallDependencies) {
+        let output = sliderule::update_dependencies(component_path);
+
+        errors.extend(output.stderr.into_iter().filter(|line| !line.is_empty()));
+    }
+
+    errors
+}
+
+/*
+ * Lists the local components held by a component, at any depth.
+ */
+pub fn list_local(component_path: &Path) -> Vec<PathBuf> {
+    let mut components: Vec<PathBuf> = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(component_path.join("components")) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            if entry.path().is_dir() {
+                components.push(entry.path());
+                components.extend(list_local(&entry.path()));
+            }
+        }
+    }
+
+    components
+}
+
+/*
+ * Checks that the .sr file is made of key: value entries and holds both licenses.
+ */
+fn check_sr(component_path: &Path, problems: &mut Vec<Problem>) {
+    let contents = match fs::read_to_string(component_path.join(".sr")) {
+        Ok(contents) => contents,
+        Err(_) => return,
+    };
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.splitn(2, ':').collect();
+        if parts.len() != 2 || parts[0].trim().is_empty() || parts[1].trim().is_empty() {
+            problems.push(Problem {
+                message: format!(
+                    "Line {} of .sr is not a valid 'key: value' entry: {}",
+                    index + 1,
+                    line
+                ),
+                fix: None,
+            });
+        }
+    }
+
+    let licenses = component::get_sr_licenses(component_path);
+    if licenses.0.is_none() {
+        problems.push(Problem {
+            message: String::from(
+                "The .sr file has no source_license entry. Use licenses change to set one.",
+            ),
+            fix: None,
+        });
+    }
+    if licenses.1.is_none() {
+        problems.push(Problem {
+            message: String::from(
+                "The .sr file has no documentation_license entry. Use licenses change to set one.",
+            ),
+            fix: None,
+        });
+    }
+}
+
+/*
+ * Checks that package.json names the component and carries the licenses from the .sr file.
+ */
+fn check_package(component_path: &Path, is_local: bool, problems: &mut Vec<Problem>) {
+    if !component_path.join("package.json").is_file() {
+        return;
+    }
+
+    let dir_name = component_path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+
+    // Local components are found by their directory name, so the two have to match
+    match component::get_name(component_path) {
+        Some(ref name) if is_local && *name != dir_name => problems.push(Problem {
+            message: format!(
+                "The name in package.json ({}) does not match the component directory ({}).",
+                name, dir_name
+            ),
+            fix: Some(Fix::SetName),
+        }),
+        Some(_) => {}
+        None => problems.push(Problem {
+            message: String::from("The package.json file has no name entry."),
+            fix: None,
+        }),
+    }
+
+    if let (Some(source), Some(doc)) = component::get_sr_licenses(component_path) {
+        match component::get_license(component_path) {
+            Some(expression) => {
+                // Top level components list the licenses of their children too, so only look for these two
+                let terms: Vec<String> = expression
+                    .replace(&['(', ')'][..], " ")
+                    .split_whitespace()
+                    .filter(|term| *term != "AND" && *term != "OR")
+                    .map(|term| term.to_string())
+                    .collect();
+
+                if !terms.contains(&source) || !terms.contains(&doc) {
+                    problems.push(Problem {
+                        message: format!(
+                            "The package.json license {} does not include the licenses from .sr ({} AND {}).",
+                            expression, source, doc
+                        ),
+                        fix: Some(Fix::SyncLicenses),
+                    });
+                }
+            }
+            None => problems.push(Problem {
+                message: String::from("The package.json file has no license entry."),
+                fix: Some(Fix::SyncLicenses),
+            }),
+        }
+    }
+}
+
+/*
+ * Looks for the most common ways that a bill of materials file stops being valid YAML.
+ */
+fn check_bom(component_path: &Path, problems: &mut Vec<Problem>) {
+    let contents = match fs::read_to_string(component_path.join("bom_data.yaml")) {
+        Ok(contents) => contents,
+        Err(_) => return,
+    };
+
+    for (index, line) in contents.lines().enumerate() {
+        let trimmed = line.trim();
+
+        let message = if trimmed.starts_with("<<<<<<<")
+            || trimmed.starts_with("=======")
+            || trimmed.starts_with(">>>>>>>")
+        {
+            "contains a merge conflict marker"
+        } else if line.trim_start_matches(' ').starts_with('\t') {
+            "is indented with a tab, which YAML does not allow"
+        } else if !trimmed.is_empty()
+            && !trimmed.starts_with('#')
+            && !trimmed.starts_with('-')
+            && !trimmed.contains(':')
+        {
+            "is not a 'key: value' entry or a list item"
+        } else {
+            continue;
+        };
+
+        problems.push(Problem {
+            message: format!("Line {} of bom_data.yaml {}.", index + 1, message),
+            fix: None,
+        });
+    }
+}
@@ -0,0 +1,127 @@
+use component;
+use std::fs;
+use std::path::Path;
+use tools;
+
+/*
+ * Makes a complete copy of a component as it was at a release, along with the remote components
+ * that were pinned at the time. The source can be the path to a component's repository or a URL.
+ * Returns the commit that the release is at, along with any warnings about the remote components.
+ */
+pub fn checkout(source: &str, release: &str, into: &Path) -> Result<(String, Vec<String>), String> {
+    // Checking out over the top of another directory would mix two designs together
+    if fs::read_dir(into)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false)
+    {
+        return Err(format!(
+            "{} already exists and is not empty.",
+            into.display()
+        ));
+    }
+
+    let parent = into.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)
+        .map_err(|e| format!("Could not create {}: {}", parent.display(), e))?;
+
+    tools::git(
+        parent,
+        &["clone", "--no-checkout", source, &into.to_string_lossy()],
+    )?;
+
+    let commit = find_release(into, release)
+        .and_then(|commit| tools::git(into, &["checkout", "--detach", &commit]).map(|_| commit));
+
+    // Do not leave a copy behind that is not at any release
+    let commit = match commit {
+        Ok(commit) => commit,
+        Err(e) => {
+            let _ = fs::remove_dir_all(into);
+            return Err(e);
+        }
+    };
+
+    Ok((commit, install_dependencies(into)))
+}
+
+/*
+ * Finds the commit of a release from its tag, or from the version recorded in package.json. A version
+ * such as 1.2.0 also matches the tag v1.2.0.
+ */
+pub fn find_release(repo_path: &Path, release: &str) -> Result<String, String> {
+    for tag in &[release.to_string(), format!("v{}", release)] {
+        let tag_ref = format!("refs/tags/{}^{{commit}}", tag);
+
+        if let Ok(commit) = tools::git(repo_path, &["rev-parse", "--verify", "-q", &tag_ref]) {
+            return Ok(commit.trim().to_string());
+        }
+    }
+
+    // Without a tag, the release is the upload that first set package.json to the version
+    let pattern = format!("\"version\": \"{}\"", release);
+    let commits = tools::git(
+        repo_path,
+        &[
+            "log",
+            "--all",
+            "--reverse",
+            "--format=%H",
+            "-G",
+            &pattern,
+            "--",
+            "package.json",
+        ],
+    )
+    .unwrap_or_default();
+
+    for commit in commits.lines() {
+        let contents = tools::git(repo_path, &["show", &format!("{}:package.json", commit)])
+            .unwrap_or_default();
+
+        let version = contents
+            .lines()
+            .find(|line| line.trim().starts_with("\"version\""))
+            .and_then(component::get_json_value);
+
+        if version.as_deref() == Some(release) {
+            return Ok(commit.to_string());
+        }
+    }
+
+    Err(format!(
+        "Could not find a release with the tag or version {}.",
+        release
+    ))
+}
+
+/*
+ * Installs the remote components of a checked out release. The lock file pins each one to the
+ * commit that was in use when the release was uploaded, so it is followed exactly when there is one.
+ */
+fn install_dependencies(component_path: &Path) -> Vec<String> {
+    let mut warnings: Vec<String> = Vec::new();
+
+    if component::get_dependencies(component_path).is_empty() {
+        return warnings;
+    }
+
+    if component_path.join("package-lock.json").exists() {
+        match tools::npm(component_path, &["ci"]) {
+            Ok(_) => return warnings,
+            Err(e) => warnings.push(format!(
+                "The lock file could not be followed exactly, so remote components were installed from package.json instead: {}",
+                e
+            )),
+        }
+    } else {
+        warnings.push(String::from(
+            "This release has no package-lock.json, so remote components that are not pinned to a commit in package.json may be newer than they were at the time.",
+        ));
+    }
+
+    if let Err(e) = tools::npm(component_path, &["install"]) {
+        warnings.push(format!("Could not install the remote components: {}", e));
+    }
+
+    warnings
+}
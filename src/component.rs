@@ -0,0 +1,605 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/*
+ * The files that every component is required to have.
+ */
+pub const FILES: [&str; 4] = [".sr", "bom_data.yaml", "package.json", "README.md"];
+
+/*
+ * The directories that every component is required to have.
+ */
+pub const DIRS: [&str; 3] = ["docs", "source", "components"];
+
+/*
+ * Checks a directory against the Sliderule component layout and returns a description of each
+ * problem that was found. An empty list means the directory is a valid component.
+ */
+pub fn find_problems(component_path: &Path) -> Vec<String> {
+    let mut problems: Vec<String> = Vec::new();
+
+    for missing in find_missing(component_path) {
+        if FILES.contains(&missing) {
+            problems.push(format!("The file {} does not exist.", missing));
+        } else {
+            problems.push(format!("The directory {} does not exist.", missing));
+        }
+    }
+
+    // The .sr file has to hold both the source and documentation licenses
+    if component_path.join(".sr").is_file() {
+        let licenses = get_sr_licenses(component_path);
+
+        if licenses.0.is_none() {
+            problems.push(String::from(
+                "The .sr file does not contain a source_license entry.",
+            ));
+        }
+        if licenses.1.is_none() {
+            problems.push(String::from(
+                "The .sr file does not contain a documentation_license entry.",
+            ));
+        }
+    }
+
+    // The component has to be named in its package.json file
+    if component_path.join("package.json").is_file() && get_name(component_path).is_none() {
+        problems.push(String::from(
+            "The package.json file does not contain a name entry.",
+        ));
+    }
+
+    problems
+}
+
+/*
+ * Lists the required files and directories that a component is missing.
+ */
+pub fn find_missing(component_path: &Path) -> Vec<&'static str> {
+    let mut missing: Vec<&'static str> = Vec::new();
+
+    for file in FILES.iter() {
+        if !component_path.join(file).is_file() {
+            missing.push(file);
+        }
+    }
+
+    for dir in DIRS.iter() {
+        if !component_path.join(dir).is_dir() {
+            missing.push(dir);
+        }
+    }
+
+    missing
+}
+
+/*
+ * Reads the source and documentation licenses out of a component's .sr file.
+ */
+pub fn get_sr_licenses(component_path: &Path) -> (Option<String>, Option<String>) {
+    let mut licenses = (None, None);
+
+    let contents = match fs::read_to_string(component_path.join(".sr")) {
+        Ok(contents) => contents,
+        Err(_) => return licenses,
+    };
+
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if line.starts_with("source_license:") {
+            licenses.0 = get_json_value(line);
+        } else if line.starts_with("documentation_license:") {
+            licenses.1 = get_json_value(line);
+        }
+    }
+
+    licenses
+}
+
+/*
+ * Reads the license expression out of a component's package.json file.
+ */
+pub fn get_license(component_path: &Path) -> Option<String> {
+    let contents = match fs::read_to_string(component_path.join("package.json")) {
+        Ok(contents) => contents,
+        Err(_) => return None,
+    };
+
+    for line in contents.lines() {
+        if line.trim().starts_with("\"license\":") {
+            return get_json_value(line);
+        }
+    }
+
+    None
+}
+
+/*
+ * Reads the name of a component out of its package.json file.
+ */
+pub fn get_name(component_path: &Path) -> Option<String> {
+    let contents = match fs::read_to_string(component_path.join("package.json")) {
+        Ok(contents) => contents,
+        Err(_) => return None,
+    };
+
+    // The name is the first entry in the package.json files that Sliderule generates
+    for line in contents.lines() {
+        if line.trim().starts_with("\"name\":") {
+            return get_json_value(line);
+        }
+    }
+
+    None
+}
+
+/*
+ * Extracts the string value from a single "key": "value" line of a JSON file.
+ */
+pub fn get_json_value(line: &str) -> Option<String> {
+    let parts: Vec<&str> = line.splitn(2, ':').collect();
+
+    if parts.len() != 2 {
+        return None;
+    }
+
+    let value = parts[1].trim().trim_end_matches(',').trim_matches('"');
+
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/*
+ * Reads the name and location of each dependency listed in a component's package.json file.
+ */
+pub fn get_dependencies(component_path: &Path) -> Vec<(String, String)> {
+    match fs::read_to_string(component_path.join("package.json")) {
+        Ok(contents) => parse_dependencies(&contents),
+        Err(_) => Vec::new(),
+    }
+}
+
+/*
+ * Reads the name and location of each dependency from the contents of a package.json file.
+ */
+pub fn parse_dependencies(contents: &str) -> Vec<(String, String)> {
+    let mut dependencies: Vec<(String, String)> = Vec::new();
+
+    let mut in_dependencies = false;
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if line.starts_with("\"dependencies\":") {
+            // An empty dependency list can open and close on the same line
+            in_dependencies = !line.ends_with('}') && !line.ends_with("},");
+            continue;
+        }
+
+        if in_dependencies {
+            if line.starts_with('}') {
+                break;
+            }
+
+            let name = line.split(':').next().unwrap().trim().trim_matches('"');
+
+            if let Some(location) = get_json_value(line) {
+                dependencies.push((name.to_string(), location));
+            }
+        }
+    }
+
+    dependencies
+}
+
+/*
+ * Copies a directory and everything in it to a new location.
+ */
+pub fn copy_dir(src: &Path, dest: &Path) -> io::Result<()> {
+    copy_dir_except(src, dest, &[])
+}
+
+/*
+ * Copies a directory to a new location, leaving out the files and directories with the given names
+ * at any depth.
+ */
+pub fn copy_dir_except(src: &Path, dest: &Path, skip: &[&str]) -> io::Result<()> {
+    fs::create_dir_all(dest)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+
+        if skip.iter().any(|name| entry.file_name() == **name) {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            copy_dir_except(&entry.path(), &target, skip)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+/*
+ * Tells whether a path is a symbolic link, like the ones npm makes for components installed from a directory.
+ */
+pub fn is_link(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|metadata| metadata.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+/*
+ * Removes a file, or a directory and everything in it, if it exists. Only the link itself is removed
+ * for symbolic links.
+ */
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    if is_link(path) {
+        // Links to directories are removed as directories on Windows
+        fs::remove_file(path).or_else(|_| fs::remove_dir(path))
+    } else if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else if path.exists() {
+        fs::remove_file(path)
+    } else {
+        Ok(())
+    }
+}
+
+/*
+ * Finds the commit that npm locked a remote dependency to in the package-lock.json file.
+ */
+pub fn get_locked_commit(component_path: &Path, name: &str) -> Option<String> {
+    let contents = match fs::read_to_string(component_path.join("package-lock.json")) {
+        Ok(contents) => contents,
+        Err(_) => return None,
+    };
+
+    // Newer lock files key packages by their node_modules path, older ones by their name
+    let keys = [
+        format!("\"node_modules/{}\": {{", name),
+        format!("\"{}\": {{", name),
+    ];
+
+    let mut in_package = false;
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if keys.iter().any(|key| line == key) {
+            in_package = true;
+            continue;
+        }
+
+        if in_package {
+            if line.starts_with('}') {
+                in_package = false;
+                continue;
+            }
+
+            // The commit is appended to the resolved git URL after a #
+            if line.starts_with("\"resolved\":") || line.starts_with("\"version\":") {
+                if let Some(value) = get_json_value(line) {
+                    if let Some(index) = value.find('#') {
+                        return Some(value[index + 1..].to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/*
+ * Replaces the dependencies listed in a package.json file.
+ */
+pub fn set_dependencies(contents: &str, dependencies: &[(String, String)]) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let start = match lines
+        .iter()
+        .position(|line| line.trim().starts_with("\"dependencies\":"))
+    {
+        Some(start) => start,
+        None => return contents.to_string(),
+    };
+
+    // An empty dependency list can open and close on the same line
+    let end = if lines[start].trim().trim_end_matches(',').ends_with('}') {
+        start
+    } else {
+        lines[start..]
+            .iter()
+            .position(|line| line.trim().starts_with('}'))
+            .map(|offset| start + offset)
+            .unwrap_or(start)
+    };
+
+    let indent = &lines[start][..lines[start].len() - lines[start].trim_start().len()];
+    let comma = if lines[end].trim_end().ends_with(',') {
+        ","
+    } else {
+        ""
+    };
+
+    let mut new_lines: Vec<String> = lines[..start].iter().map(|line| line.to_string()).collect();
+
+    if dependencies.is_empty() {
+        new_lines.push(format!("{}\"dependencies\": {{}}{}", indent, comma));
+    } else {
+        new_lines.push(format!("{}\"dependencies\": {{", indent));
+
+        let entries: Vec<String> = dependencies
+            .iter()
+            .map(|(name, location)| format!("{}  \"{}\": \"{}\"", indent, name, location))
+            .collect();
+        new_lines.push(entries.join(",\n"));
+
+        new_lines.push(format!("{}}}{}", indent, comma));
+    }
+
+    new_lines.extend(lines[end + 1..].iter().map(|line| line.to_string()));
+
+    let mut new_contents = new_lines.join("\n");
+    if contents.ends_with('\n') {
+        new_contents.push('\n');
+    }
+
+    new_contents
+}
+
+/*
+ * Strips the npm specific parts of a dependency location so that git can use it.
+ */
+pub fn get_git_url(location: &str) -> String {
+    let url = location.trim_start_matches("git+");
+
+    // Drop any branch or commit that npm has pinned the dependency to
+    match url.find('#') {
+        Some(index) => url[..index].to_string(),
+        None => url.to_string(),
+    }
+}
+
+/*
+ * Changes the name recorded in a component's package.json, README.md and bom_data.yaml files.
+ */
+pub fn set_name(component_path: &Path, old_name: &str, new_name: &str) -> io::Result<()> {
+    replace_in_file(
+        &component_path.join("package.json"),
+        &format!("\"name\": \"{}\"", old_name),
+        &format!("\"name\": \"{}\"", new_name),
+    )?;
+    replace_in_file(
+        &component_path.join("README.md"),
+        &format!("# {}", old_name),
+        &format!("# {}", new_name),
+    )?;
+    replace_in_file(
+        &component_path.join("bom_data.yaml"),
+        &format!("# Bill of Materials Data for {}", old_name),
+        &format!("# Bill of Materials Data for {}", new_name),
+    )?;
+
+    Ok(())
+}
+
+/*
+ * Sets the version of a component in its package.json file.
+ */
+pub fn set_version(component_path: &Path, version: &str) -> io::Result<()> {
+    let package_file = component_path.join("package.json");
+    let contents = fs::read_to_string(&package_file)?;
+
+    let lines: Vec<String> = contents
+        .lines()
+        .map(|line| {
+            if line.trim().starts_with("\"version\":") {
+                let indent = &line[..line.len() - line.trim_start().len()];
+                let comma = if line.trim_end().ends_with(',') {
+                    ","
+                } else {
+                    ""
+                };
+
+                format!("{}\"version\": \"{}\"{}", indent, version, comma)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    let mut new_contents = lines.join("\n");
+    if contents.ends_with('\n') {
+        new_contents.push('\n');
+    }
+
+    fs::write(&package_file, new_contents)
+}
+
+/*
+ * Searches the local components of a project, at any depth, for one with the given name.
+ */
+pub fn find_local(project_path: &Path, name: &str) -> Option<PathBuf> {
+    let components_dir = project_path.join("components");
+
+    let entries = match fs::read_dir(&components_dir) {
+        Ok(entries) => entries,
+        Err(_) => return None,
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if !entry.path().is_dir() {
+            continue;
+        }
+
+        if entry.file_name().to_string_lossy() == name {
+            return Some(entry.path());
+        }
+
+        if let Some(path) = find_local(&entry.path(), name) {
+            return Some(path);
+        }
+    }
+
+    None
+}
+
+/*
+ * Gets the number of seconds since the UNIX epoch.
+ */
+pub fn get_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("ERROR: System time is set before the UNIX epoch.")
+        .as_secs()
+}
+
+/*
+ * Gets the date of a time given in seconds since the UNIX epoch, in YYYY-MM-DD form.
+ */
+pub fn get_date(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+
+    // Converts days since the UNIX epoch to a calendar date (Howard Hinnant's civil_from_days)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/*
+ * Replaces the first line of a file that matches the old text exactly (ignoring surrounding
+ * whitespace), leaving the file alone if there is no such line.
+ */
+fn replace_in_file(file_path: &Path, old_text: &str, new_text: &str) -> io::Result<()> {
+    if !file_path.exists() {
+        return Ok(());
+    }
+
+    let contents = fs::read_to_string(file_path)?;
+    let mut replaced = false;
+
+    let lines: Vec<String> = contents
+        .lines()
+        .map(|line| {
+            if !replaced && line.trim().trim_end_matches(',') == old_text {
+                replaced = true;
+                line.replacen(old_text, new_text, 1)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    let mut new_contents = lines.join("\n");
+    if contents.ends_with('\n') {
+        new_contents.push('\n');
+    }
+
+    fs::write(file_path, new_contents)
+}
+
+/*
+ * Splits a bill of materials into the comments at its top and its entries. Entries are keyed by
+ * their top level key, or by section/key for the parts listed under a section such as parts. Each
+ * entry holds all of the lines that are nested under it.
+ */
+pub fn parse_bom(contents: &str) -> (String, Vec<(String, String)>) {
+    let mut header = String::new();
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut section = String::new();
+
+    for line in contents.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+
+        if entries.is_empty() && (trimmed.is_empty() || trimmed.starts_with('#')) {
+            header.push_str(&format!("{}\n", line));
+            continue;
+        }
+
+        if indent == 0 && !trimmed.is_empty() {
+            let key = trimmed.split(':').next().unwrap().to_string();
+
+            // A section heading, such as parts, has its parts as entries of their own
+            section = if trimmed.ends_with(':') {
+                key.to_string()
+            } else {
+                String::new()
+            };
+
+            entries.push((key, format!("{}\n", line)));
+        } else if !section.is_empty() && indent == 2 && !trimmed.is_empty() {
+            let name = if trimmed.starts_with('-') {
+                trimmed
+            } else {
+                trimmed.split(':').next().unwrap()
+            };
+
+            entries.push((format!("{}/{}", section, name), format!("{}\n", line)));
+        } else if let Some(last) = entries.last_mut() {
+            last.1.push_str(&format!("{}\n", line));
+        } else {
+            header.push_str(&format!("{}\n", line));
+        }
+    }
+
+    (header, entries)
+}
+
+/*
+ * Replaces whole-word uses of a component name in some text, so that names which merely contain
+ * the old name are left alone.
+ */
+pub fn replace_name(text: &str, old_name: &str, new_name: &str) -> String {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+
+    let mut result = String::new();
+    let mut rest = text;
+
+    while let Some(index) = rest.find(old_name) {
+        let before = rest[..index].chars().last();
+        let after = rest[index + old_name.len()..].chars().next();
+
+        result.push_str(&rest[..index]);
+
+        // YAML list entries like -component_1 put a dash right in front of the name
+        let is_list_entry = before == Some('-')
+            && rest[..index - 1]
+                .rsplit('\n')
+                .next()
+                .map_or(true, |line| line.trim().is_empty());
+
+        let starts_word = before.map_or(true, |c| !is_name_char(c)) || is_list_entry;
+        let ends_word = after.map_or(true, |c| !is_name_char(c));
+
+        if starts_word && ends_word {
+            result.push_str(new_name);
+        } else {
+            result.push_str(old_name);
+        }
+
+        rest = &rest[index + old_name.len()..];
+    }
+
+    result.push_str(rest);
+
+    result
+}
@@ -0,0 +1,369 @@
+use component;
+use std::fs;
+use std::path::Path;
+use tools;
+use worktree;
+
+/*
+ * The side of a conflict to keep when both sides changed the same thing.
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+/*
+ * A file that git could not merge, and whether Sliderule knows how to merge it.
+ */
+pub struct Conflict {
+    pub component: String,
+    pub file: String,
+    pub can_merge: bool,
+}
+
+/*
+ * Lists the files that git could not merge, along with the component each one belongs to.
+ */
+pub fn list(project_path: &Path) -> Vec<Conflict> {
+    let mut conflicts: Vec<Conflict> = Vec::new();
+
+    for (component, files) in worktree::list_conflicts(project_path) {
+        for file in files {
+            let path = if component == "." {
+                file.to_string()
+            } else {
+                format!("{}/{}", component, file)
+            };
+
+            conflicts.push(Conflict {
+                component: component.to_string(),
+                can_merge: is_component_file(&path),
+                file: path,
+            });
+        }
+    }
+
+    conflicts
+}
+
+/*
+ * Resolves the conflicts in a file and marks it as merged. The package.json, .sr and bom_data.yaml
+ * files of a component are merged entry by entry, so a side only has to be chosen for the entries
+ * that both sides changed. Any other file is replaced with the chosen side's version. Returns a
+ * description of each choice that was made.
+ */
+pub fn resolve(project_path: &Path, file: &str, side: Option<Side>) -> Result<Vec<String>, String> {
+    let file = file.replace('\\', "/");
+    let file = file.trim_start_matches("./");
+
+    if !list(project_path)
+        .iter()
+        .any(|conflict| conflict.file == file)
+    {
+        return Err(format!("{} does not have any merge conflicts.", file));
+    }
+
+    let base = get_stage(project_path, file, 1);
+    let ours = get_stage(project_path, file, 2);
+    let theirs = get_stage(project_path, file, 3);
+
+    let mut notes: Vec<String> = Vec::new();
+
+    let merged = match (is_component_file(file), &ours, &theirs) {
+        (true, Some(ours), Some(theirs)) => {
+            let base = base.unwrap_or_default();
+            let mut clashes: Vec<String> = Vec::new();
+
+            let merged = if file.ends_with("package.json") {
+                merge_package(&base, ours, theirs, side, &mut clashes)
+            } else if file.ends_with(".sr") {
+                merge_sr(&base, ours, theirs, side, &mut clashes)
+            } else {
+                merge_bom(&base, ours, theirs, side, &mut clashes)
+            };
+
+            // Without a side there is no way to know which change the user wants to keep
+            if side.is_none() && !clashes.is_empty() {
+                return Err(format!(
+                    "Both sides changed {} in {}. Run conflicts resolve again with --ours to keep the changes made here, or --theirs to keep the downloaded changes.",
+                    clashes.join(", "),
+                    file
+                ));
+            }
+
+            for clash in clashes {
+                notes.push(format!("Kept {} version of {}.", describe(side), clash));
+            }
+
+            if file.ends_with("package.json") {
+                notes.push(String::from(
+                    "Run download dependencies to install the merged remote components.",
+                ));
+            }
+
+            Some(merged)
+        }
+        _ => {
+            let (flag, chosen) = match side {
+                Some(Side::Ours) => ("--ours", ours),
+                Some(Side::Theirs) => ("--theirs", theirs),
+                None => {
+                    return Err(format!(
+                        "{} cannot be merged automatically. Run conflicts resolve again with --ours to keep the changes made here, or --theirs to keep the downloaded changes.",
+                        file
+                    ))
+                }
+            };
+
+            // Let git write the file itself so that binary files such as CAD models come through intact
+            if chosen.is_some() {
+                tools::git(project_path, &["checkout", flag, "--", file])?;
+                tools::git(project_path, &["add", "--", file])?;
+
+                return Ok(notes);
+            }
+
+            None
+        }
+    };
+
+    match merged {
+        Some(contents) => {
+            fs::write(project_path.join(file), contents)
+                .map_err(|e| format!("Could not write {}: {}", file, e))?;
+            tools::git(project_path, &["add", "--", file])?;
+        }
+        None => {
+            // The chosen side deleted the file
+            tools::git(project_path, &["rm", "--quiet", "--", file])?;
+            notes.push(format!("Removed {}.", file));
+        }
+    }
+
+    Ok(notes)
+}
+
+/*
+ * Tells whether a file is one of the component files that can be merged entry by entry.
+ */
+fn is_component_file(file: &str) -> bool {
+    let name = file.rsplit('/').next().unwrap_or(file);
+
+    name == "package.json" || name == ".sr" || name == "bom_data.yaml"
+}
+
+/*
+ * Describes a side of a conflict for the user.
+ */
+fn describe(side: Option<Side>) -> &'static str {
+    match side {
+        Some(Side::Theirs) => "the downloaded",
+        _ => "the local",
+    }
+}
+
+/*
+ * Gets one side of a conflicted file from git: 1 is the common ancestor, 2 is the local version
+ * and 3 is the downloaded version. None means the file does not exist on that side.
+ */
+fn get_stage(project_path: &Path, file: &str, stage: u8) -> Option<String> {
+    tools::git(project_path, &["show", &format!(":{}:./{}", stage, file)]).ok()
+}
+
+/*
+ * Merges the dependencies of two versions of a package.json file. Everything else is taken from
+ * the local version, unless the downloaded version was chosen.
+ */
+fn merge_package(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    side: Option<Side>,
+    clashes: &mut Vec<String>,
+) -> String {
+    let dependencies = merge_entries(
+        &component::parse_dependencies(base),
+        &component::parse_dependencies(ours),
+        &component::parse_dependencies(theirs),
+        side,
+        clashes,
+    );
+
+    // The rest of the file is taken whole from the side that changed it
+    let base_rest = strip_dependencies(base);
+    let ours_rest = strip_dependencies(ours);
+    let theirs_rest = strip_dependencies(theirs);
+
+    let primary = if ours_rest == theirs_rest || theirs_rest == base_rest {
+        ours
+    } else if ours_rest == base_rest {
+        theirs
+    } else {
+        clashes.push(String::from("the package.json settings"));
+
+        if side == Some(Side::Theirs) {
+            theirs
+        } else {
+            ours
+        }
+    };
+
+    component::set_dependencies(primary, &dependencies)
+}
+
+/*
+ * Merges two versions of a .sr file key by key.
+ */
+fn merge_sr(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    side: Option<Side>,
+    clashes: &mut Vec<String>,
+) -> String {
+    let merged = merge_entries(
+        &parse_sr(base),
+        &parse_sr(ours),
+        &parse_sr(theirs),
+        side,
+        clashes,
+    );
+
+    let mut contents: String = merged
+        .iter()
+        .map(|(key, value)| format!("{}: {}\n", key, value))
+        .collect();
+
+    if !ours.ends_with('\n') {
+        contents.pop();
+    }
+
+    contents
+}
+
+/*
+ * Merges two versions of a bom_data.yaml file part by part.
+ */
+fn merge_bom(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    side: Option<Side>,
+    clashes: &mut Vec<String>,
+) -> String {
+    let (ours_header, ours_entries) = component::parse_bom(ours);
+    let (theirs_header, theirs_entries) = component::parse_bom(theirs);
+
+    let merged = merge_entries(
+        &component::parse_bom(base).1,
+        &ours_entries,
+        &theirs_entries,
+        side,
+        clashes,
+    );
+
+    let mut contents = if side == Some(Side::Theirs) {
+        theirs_header
+    } else {
+        ours_header
+    };
+
+    for (_, text) in merged {
+        contents.push_str(&text);
+    }
+
+    contents
+}
+
+/*
+ * Merges two lists of keyed entries against the list they both started from. Entries that only one
+ * side changed, added or removed take that side's version. When both sides changed an entry in
+ * different ways, the chosen side is used and the entry is recorded as a clash.
+ */
+fn merge_entries(
+    base: &[(String, String)],
+    ours: &[(String, String)],
+    theirs: &[(String, String)],
+    side: Option<Side>,
+    clashes: &mut Vec<String>,
+) -> Vec<(String, String)> {
+    let find = |entries: &[(String, String)], key: &str| {
+        entries
+            .iter()
+            .find(|entry| entry.0 == key)
+            .map(|entry| entry.1.to_string())
+    };
+
+    // Entries keep the local order, with downloaded entries placed after the entry they followed
+    let mut keys: Vec<String> = ours.iter().map(|entry| entry.0.to_string()).collect();
+    for (index, entry) in theirs.iter().enumerate() {
+        if keys.contains(&entry.0) {
+            continue;
+        }
+
+        let position = if index == 0 {
+            0
+        } else {
+            keys.iter()
+                .position(|key| *key == theirs[index - 1].0)
+                .map(|position| position + 1)
+                .unwrap_or_else(|| keys.len())
+        };
+
+        keys.insert(position, entry.0.to_string());
+    }
+
+    let mut merged: Vec<(String, String)> = Vec::new();
+    for key in keys {
+        let base_value = find(base, &key);
+        let ours_value = find(ours, &key);
+        let theirs_value = find(theirs, &key);
+
+        let value = if ours_value == theirs_value || theirs_value == base_value {
+            ours_value
+        } else if ours_value == base_value {
+            theirs_value
+        } else {
+            clashes.push(key.to_string());
+
+            if side == Some(Side::Theirs) {
+                theirs_value
+            } else {
+                ours_value
+            }
+        };
+
+        if let Some(value) = value {
+            merged.push((key, value));
+        }
+    }
+
+    merged
+}
+
+/*
+ * Reads the key: value entries of a .sr file.
+ */
+fn parse_sr(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, ':');
+
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) if !key.trim().is_empty() => {
+                    Some((key.trim().to_string(), value.trim().to_string()))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/*
+ * Removes the dependencies block from a package.json file so that the rest of the file can be compared.
+ */
+fn strip_dependencies(contents: &str) -> String {
+    component::set_dependencies(contents, &[])
+}
@@ -0,0 +1,52 @@
+use component;
+use std::fs;
+use std::path::Path;
+
+/*
+ * Makes a new local component from a copy of an existing local or remote component, so that
+ * variants of a design can start from the original.
+ */
+pub fn copy_component(project_path: &Path, name: &str, new_name: &str) -> Result<(), String> {
+    // Local components take priority over remote components with the same name
+    let source_path = match component::find_local(project_path, name) {
+        Some(path) => path,
+        None => {
+            let installed_dir = project_path.join("node_modules").join(name);
+
+            if !installed_dir.exists() {
+                return Err(format!(
+                    "There is no local or remote component named {}.",
+                    name
+                ));
+            }
+
+            installed_dir
+        }
+    };
+
+    let new_path = project_path.join("components").join(new_name);
+    if new_path.exists() || component::find_local(project_path, new_name).is_some() {
+        return Err(format!("A component named {} already exists.", new_name));
+    }
+
+    component::copy_dir(&source_path, &new_path)
+        .map_err(|e| format!("Could not copy {}: {}", name, e))?;
+
+    // The copy is a new design, so it does not carry the repository or installed packages of the original
+    for dir in &[".git", "node_modules"] {
+        if new_path.join(dir).exists() {
+            fs::remove_dir_all(new_path.join(dir))
+                .map_err(|e| format!("Could not remove {} from the copy: {}", dir, e))?;
+        }
+    }
+
+    // Remote components can have been published under a different name than their directory
+    let old_name = component::get_name(&new_path).unwrap_or_else(|| name.to_string());
+
+    component::set_name(&new_path, &old_name, new_name)
+        .map_err(|e| format!("Could not update the files of {}: {}", new_name, e))?;
+    component::set_version(&new_path, "1.0.0")
+        .map_err(|e| format!("Could not reset the version of {}: {}", new_name, e))?;
+
+    Ok(())
+}
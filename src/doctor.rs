@@ -0,0 +1,299 @@
+use lfs;
+use provision;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tools;
+
+/*
+ * The outcome of a single environment check.
+ */
+pub enum Status {
+    Pass,
+    Fail,
+    Skip,
+}
+
+/*
+ * The result of checking one of the prerequisites of Sliderule, with details or advice for the user.
+ */
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+}
+
+/*
+ * Checks the external tools and settings that Sliderule relies on. Reaching remote repositories
+ * over the network is skipped in offline mode.
+ */
+pub fn check_env(dir: &Path, offline: bool) -> Vec<Check> {
+    let mut checks: Vec<Check> = Vec::new();
+
+    let git_version = tools::git(dir, &["--version"]);
+    checks.push(match git_version {
+        Ok(ref version) => pass("git", version.trim()),
+        Err(_) => fail(
+            "git",
+            "git was not found on the PATH. Install it from https://git-scm.com/downloads",
+        ),
+    });
+
+    // Offline mode and Git LFS pass their settings to git through the environment, which older versions ignore
+    if let Ok(ref version) = git_version {
+        if !tools::git_reads_env_config() {
+            checks.push(if offline || lfs::is_used(dir) {
+                fail(
+                    "git version",
+                    &format!(
+                        "{} is too old for --offline and Git LFS. Update git to version 2.31 or newer",
+                        version.trim()
+                    ),
+                )
+            } else {
+                Check {
+                    name: String::from("git version"),
+                    status: Status::Skip,
+                    detail: format!(
+                        "{} is older than 2.31, so --offline and Git LFS cannot be used",
+                        version.trim()
+                    ),
+                }
+            });
+        }
+    }
+
+    checks.push(match tools::npm(dir, &["--version"]) {
+        Ok(version) => pass("npm", &format!("npm {}", version.trim())),
+        Err(_) => fail(
+            "npm",
+            "npm was not found on the PATH. Install it from https://www.npmjs.com/get-npm",
+        ),
+    });
+
+    // git refuses to make commits during an upload without these
+    for setting in &["user.name", "user.email"] {
+        checks.push(match tools::git(dir, &["config", setting]) {
+            Ok(ref value) if !value.trim().is_empty() => {
+                pass(&format!("git {}", setting), value.trim())
+            }
+            _ => fail(
+                &format!("git {}", setting),
+                &format!("Not set. Run: git config --global {} \"...\"", setting),
+            ),
+        });
+    }
+
+    // Git LFS is only needed by components that keep large files in it
+    checks.push(match tools::git(dir, &["lfs", "version"]) {
+        Ok(version) => pass("git-lfs", version.trim()),
+        Err(_) if lfs::is_used(dir) => fail(
+            "git-lfs",
+            "This component keeps large files in Git LFS, but git-lfs was not found. Install it from https://git-lfs.github.com",
+        ),
+        Err(_) => Check {
+            name: String::from("git-lfs"),
+            status: Status::Skip,
+            detail: String::from("Not installed, so large files are stored in git directly"),
+        },
+    });
+
+    // curl is only needed to create repositories on the servers in the servers file
+    checks.push(match tools::curl(&["--version"], "") {
+        Ok(version) => pass("curl", version.lines().next().unwrap_or("").trim()),
+        Err(_) if provision::get_servers_file().exists() => fail(
+            "curl",
+            "curl was not found on the PATH, so --create-remote cannot create repositories on the servers in the servers file. Install it from https://curl.se/download.html",
+        ),
+        Err(_) => Check {
+            name: String::from("curl"),
+            status: Status::Skip,
+            detail: String::from("Not installed, but no servers are configured for --create-remote"),
+        },
+    });
+
+    // SSH is only needed by components that have a remote with an ssh URL
+    if uses_ssh(dir) {
+        checks.push(check_ssh_agent());
+        checks.push(check_ssh_keys());
+    } else {
+        for name in &["SSH agent", "SSH key"] {
+            checks.push(Check {
+                name: name.to_string(),
+                status: Status::Skip,
+                detail: String::from("No remote of this component uses ssh"),
+            });
+        }
+    }
+
+    checks.extend(check_remotes(dir, offline));
+
+    checks
+}
+
+/*
+ * Tells whether any remote of a component is reached over ssh, either with an ssh:// URL or with an
+ * scp-like URL such as git@host:path.
+ */
+fn uses_ssh(dir: &Path) -> bool {
+    let remotes = match tools::git(dir, &["remote", "-v"]) {
+        Ok(remotes) => remotes,
+        Err(_) => return false,
+    };
+
+    remotes
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .any(|url| {
+            url.starts_with("ssh://") || url.starts_with("git+ssh://") || provision::is_scp_url(url)
+        })
+}
+
+/*
+ * Checks that an SSH agent is running and has keys loaded into it.
+ */
+fn check_ssh_agent() -> Check {
+    let output = Command::new("ssh-add").arg("-l").output();
+
+    // ssh-add exits with 1 when the agent has no keys, and 2 when it cannot reach an agent at all
+    match output {
+        Ok(ref out) if out.status.success() => pass("SSH agent", "Running with keys loaded"),
+        Ok(ref out) if out.status.code() == Some(1) => {
+            fail("SSH agent", "Running, but no keys are loaded. Run: ssh-add")
+        }
+        Ok(_) => fail(
+            "SSH agent",
+            "Not running. Start ssh-agent and add your key with ssh-add",
+        ),
+        Err(_) => fail(
+            "SSH agent",
+            "ssh-add was not found on the PATH. Install an SSH client",
+        ),
+    }
+}
+
+/*
+ * Checks that the user has an SSH key for uploading over ssh URLs.
+ */
+fn check_ssh_keys() -> Check {
+    let home = env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .unwrap_or_else(|_| String::from("."));
+    let ssh_dir = PathBuf::from(home).join(".ssh");
+
+    let keys: Vec<&str> = ["id_ed25519", "id_ecdsa", "id_rsa", "id_dsa"]
+        .iter()
+        .filter(|key| ssh_dir.join(key).exists())
+        .cloned()
+        .collect();
+
+    if keys.is_empty() {
+        fail(
+            "SSH key",
+            &format!(
+                "No key found in {}. Create one with: ssh-keygen -t ed25519",
+                ssh_dir.display()
+            ),
+        )
+    } else {
+        pass("SSH key", &keys.join(", "))
+    }
+}
+
+/*
+ * Checks that each git remote of the current component can be reached.
+ */
+fn check_remotes(dir: &Path, offline: bool) -> Vec<Check> {
+    let mut checks: Vec<Check> = Vec::new();
+
+    // Components that have never been uploaded do not have any remotes to check
+    let remotes = match tools::git(dir, &["remote"]) {
+        Ok(remotes) => remotes,
+        Err(_) => return checks,
+    };
+
+    for remote in remotes
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+    {
+        let name = format!("remote {}", remote);
+
+        let url = match tools::git(dir, &["remote", "get-url", remote]) {
+            Ok(url) => url.trim().to_string(),
+            Err(e) => {
+                checks.push(fail(&name, &e));
+                continue;
+            }
+        };
+
+        // Repositories on the local filesystem only need to exist
+        if provision::is_local_url(&url) {
+            let path = PathBuf::from(url.trim_start_matches("file://"));
+
+            checks.push(if path.exists() {
+                pass(&name, &url)
+            } else {
+                fail(&name, &format!("{} does not exist", url))
+            });
+
+            continue;
+        }
+
+        if offline {
+            checks.push(Check {
+                name,
+                status: Status::Skip,
+                detail: format!("{} not checked in offline mode", url),
+            });
+            continue;
+        }
+
+        // Make sure git fails instead of waiting for a password or host key confirmation
+        let mut command = Command::new("git");
+        command
+            .args(&["ls-remote", "--heads", remote])
+            .current_dir(dir)
+            .env("GIT_TERMINAL_PROMPT", "0");
+        if env::var("GIT_SSH_COMMAND").is_err() {
+            command.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+        }
+
+        checks.push(match command.output() {
+            Ok(ref out) if out.status.success() => pass(&name, &url),
+            Ok(out) => fail(
+                &name,
+                &format!(
+                    "{} could not be reached: {}",
+                    url,
+                    String::from_utf8_lossy(&out.stderr).trim()
+                ),
+            ),
+            Err(e) => fail(&name, &format!("{} could not be reached: {}", url, e)),
+        });
+    }
+
+    checks
+}
+
+/*
+ * Makes the result of a check that passed.
+ */
+fn pass(name: &str, detail: &str) -> Check {
+    Check {
+        name: name.to_string(),
+        status: Status::Pass,
+        detail: detail.to_string(),
+    }
+}
+
+/*
+ * Makes the result of a check that failed, with advice for fixing it.
+ */
+fn fail(name: &str, detail: &str) -> Check {
+    Check {
+        name: name.to_string(),
+        status: Status::Fail,
+        detail: detail.to_string(),
+    }
+}
@@ -0,0 +1,387 @@
+use branch;
+use component;
+use nested;
+use remote;
+use std::fs;
+use std::path::{Path, PathBuf};
+use template;
+use tools;
+
+/*
+ * Lists what creating a component would do.
+ */
+pub fn plan_create(
+    project_path: &Path,
+    component_path: &Path,
+    template: Option<&str>,
+) -> Vec<String> {
+    let location = get_relative(project_path, component_path);
+    let mut steps = vec![format!("create directory {}", location)];
+
+    for dir in &component::DIRS {
+        steps.push(format!("create directory {}/{}", location, dir));
+    }
+    for file in &component::FILES {
+        steps.push(format!("write {}/{}", location, file));
+    }
+
+    if let Some(template) = template {
+        if template::is_url(template) {
+            steps.push(format!(
+                "download the template {} and copy its files over {}",
+                template, location
+            ));
+        } else {
+            steps.push(format!(
+                "copy the files of template {} over {}",
+                template, location
+            ));
+        }
+    }
+
+    steps.push(format!(
+        "add the Git LFS patterns for large files to {}/.gitattributes",
+        location
+    ));
+
+    // Components created inside of another one change that component's licenses
+    if project_path.join(".sr").exists() {
+        steps.push(String::from("update the license in package.json"));
+    }
+
+    steps
+}
+
+/*
+ * Lists what adding a remote or local component would do.
+ */
+pub fn plan_add(
+    project_path: &Path,
+    url: &str,
+    name: &str,
+    cache_dir: Option<&Path>,
+) -> Vec<String> {
+    let mut steps = vec![
+        format!("npm install --save {}", url),
+        String::from("update package.json and package-lock.json"),
+    ];
+
+    if !name.is_empty() {
+        steps.push(format!("create directory node_modules/{}", name));
+    }
+
+    if project_path.join(".sr").exists() {
+        steps.push(String::from("update the license in package.json"));
+    }
+
+    if let Some(dir) = cache_dir {
+        steps.push(format!("store {} in the cache at {}", url, dir.display()));
+    }
+
+    steps
+}
+
+/*
+ * Lists what removing a component would do.
+ */
+pub fn plan_remove(project_path: &Path, name: &str) -> Vec<String> {
+    let local_path = project_path.join("components").join(name);
+    let mut steps: Vec<String> = Vec::new();
+
+    if local_path.is_dir() {
+        steps.push(format!("copy components/{} into the trash", name));
+        steps.push(format!(
+            "delete directory components/{} ({} files)",
+            name,
+            list_files(&local_path).len()
+        ));
+    } else if component::get_dependencies(project_path)
+        .iter()
+        .any(|dependency| dependency.0 == name)
+    {
+        steps.push(format!(
+            "record the package.json entry of {} in the trash",
+            name
+        ));
+        steps.push(format!("npm uninstall --save {}", name));
+        steps.push(String::from("update package.json and package-lock.json"));
+        steps.push(format!("delete directory node_modules/{}", name));
+    } else {
+        steps.push(format!(
+            "nothing, there is no component named {} in this project",
+            name
+        ));
+        return steps;
+    }
+
+    steps.push(String::from("update the license in package.json"));
+
+    steps
+}
+
+/*
+ * Lists what turning a local component into a remote component would do.
+ */
+pub fn plan_refactor(project_path: &Path, name: &str, url: &str) -> Vec<String> {
+    let local_path = project_path.join("components").join(name);
+
+    if !local_path.is_dir() {
+        return vec![format!(
+            "nothing, there is no local component named {} in this project",
+            name
+        )];
+    }
+
+    let mut steps: Vec<String> = Vec::new();
+
+    if !local_path.join(".git").exists() {
+        steps.push(format!("git init in components/{}", name));
+    }
+
+    for file in list_files(&local_path) {
+        steps.push(format!("upload components/{}/{}", name, file));
+    }
+
+    steps.push(format!(
+        "git push the files of components/{} to {}",
+        name, url
+    ));
+    steps.push(format!("delete directory components/{}", name));
+    steps.push(format!("npm install --save {}", url));
+    steps.push(String::from("update package.json and package-lock.json"));
+    steps.push(format!("create directory node_modules/{}", name));
+
+    steps
+}
+
+/*
+ * Lists what uploading the changes to a component would do, including each file that would be pushed.
+ */
+pub fn plan_upload(
+    project_path: &Path,
+    remote_name: &str,
+    message: &str,
+    url: &str,
+) -> Vec<String> {
+    let mut steps: Vec<String> = Vec::new();
+
+    // Components that have not been uploaded before have every file pushed
+    if !project_path.join(".git").exists() {
+        steps.push(String::from("git init"));
+        steps.push(format!("git remote add {} {}", remote_name, url));
+
+        for file in list_files(project_path) {
+            steps.push(format!("upload {}", file));
+        }
+    } else {
+        let status = tools::git(
+            project_path,
+            &["status", "--porcelain", "--untracked-files=all"],
+        )
+        .unwrap_or_default();
+
+        for line in status.lines().filter(|line| line.len() > 3) {
+            let change = match line[..2].trim() {
+                "D" => "delete",
+                "??" | "A" => "add",
+                _ => "upload",
+            };
+
+            steps.push(format!("{} {}", change, &line[3..]));
+        }
+
+        if status.trim().is_empty() {
+            steps.push(String::from("no files have changed since the last upload"));
+        }
+    }
+
+    let remote = if url.is_empty() {
+        remote::get_url(project_path, remote_name).unwrap_or_else(|_| remote_name.to_string())
+    } else {
+        url.to_string()
+    };
+
+    steps.push(String::from("git add ."));
+    steps.push(format!("git commit -m \"{}\"", message));
+    steps.push(format!("git push {} {}", remote, get_branch(project_path)));
+
+    steps
+}
+
+/*
+ * Lists what uploading the components inside of a component that have their own repositories would do.
+ * The steps come back as an error when the upload would stop before reaching the component itself.
+ */
+pub fn plan_upload_children(
+    project_path: &Path,
+    remote_name: &str,
+    message: &str,
+) -> Result<Vec<String>, Vec<String>> {
+    let mut steps: Vec<String> = Vec::new();
+
+    for child in nested::list_child_repos(project_path) {
+        if !nested::has_changes(&child) {
+            continue;
+        }
+
+        let location = get_relative(project_path, &child);
+
+        if remote::get_url(&child, remote_name).is_err() {
+            if remote_name != remote::DEFAULT_REMOTE {
+                steps.push(format!(
+                    "stop, since {} has no remote named {}",
+                    location, remote_name
+                ));
+                return Err(steps);
+            }
+
+            steps.push(format!(
+                "skip {}, which has not been uploaded to a repository yet",
+                location
+            ));
+            continue;
+        }
+
+        steps.push(format!(
+            "upload the changes of {} with the message \"{}\"",
+            location, message
+        ));
+
+        // Remote components are locked to a commit by the component that installed them
+        let holder = child.parent().unwrap();
+        if holder
+            .file_name()
+            .map(|name| name == "node_modules")
+            .unwrap_or(false)
+        {
+            let parent = get_relative(project_path, holder.parent().unwrap());
+            let lock_file = if parent.is_empty() {
+                String::from("package-lock.json")
+            } else {
+                format!("{}/package-lock.json", parent)
+            };
+
+            steps.push(format!(
+                "update the commit of {} in {}",
+                child.file_name().unwrap().to_string_lossy(),
+                lock_file
+            ));
+        }
+    }
+
+    Ok(steps)
+}
+
+/*
+ * Lists what downloading a component, its changes or its dependencies would do.
+ */
+pub fn plan_download(
+    project_path: &Path,
+    remote_name: &str,
+    subcommand: &str,
+    offline: bool,
+) -> Vec<String> {
+    let mut steps: Vec<String> = Vec::new();
+
+    if subcommand.contains('/') {
+        steps.push(format!("git clone {}", subcommand));
+        if !offline {
+            steps.push(format!("store {} in the cache", subcommand));
+        }
+
+        return steps;
+    }
+
+    if subcommand == "all" && !offline {
+        steps.push(format!(
+            "git pull {} {}",
+            remote_name,
+            get_branch(project_path)
+        ));
+    }
+
+    steps.push(String::from("npm install"));
+    for (name, _) in component::get_dependencies(project_path) {
+        steps.push(format!("update node_modules/{}", name));
+    }
+
+    if !offline {
+        steps.push(String::from("store the remote components in the cache"));
+    }
+
+    steps
+}
+
+/*
+ * Lists what changing the licenses of a component would do.
+ */
+pub fn plan_change_licenses(project_path: &Path, source: &str, doc: &str) -> Vec<String> {
+    let (old_source, old_doc) = component::get_sr_licenses(project_path);
+
+    vec![
+        format!(
+            "change source_license in .sr from {} to {}",
+            old_source.unwrap_or_else(|| String::from("none")),
+            source
+        ),
+        format!(
+            "change documentation_license in .sr from {} to {}",
+            old_doc.unwrap_or_else(|| String::from("none")),
+            doc
+        ),
+        format!(
+            "update the license in package.json from {}",
+            component::get_license(project_path).unwrap_or_else(|| String::from("none"))
+        ),
+    ]
+}
+
+/*
+ * Gets the branch that git is working on, falling back to the one that Sliderule uses by default.
+ */
+fn get_branch(project_path: &Path) -> String {
+    branch::current(project_path).unwrap_or_else(|| branch::get_default(project_path))
+}
+
+/*
+ * Lists the files in a directory, at any depth, leaving out git's files and installed components.
+ */
+fn list_files(dir: &Path) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    let mut pending: Vec<PathBuf> = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let entries = match fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            if file_name == ".git" || file_name == "node_modules" {
+                continue;
+            }
+
+            if entry.path().is_dir() {
+                pending.push(entry.path());
+            } else {
+                files.push(get_relative(dir, &entry.path()));
+            }
+        }
+    }
+
+    files.sort();
+
+    files
+}
+
+/*
+ * Gets a path relative to a directory, with forward slashes so that it reads the same on every platform.
+ */
+fn get_relative(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
@@ -0,0 +1,137 @@
+use component;
+use get_temp_dir;
+use sliderule;
+use std::fs;
+use std::path::Path;
+
+/*
+ * Turns an existing directory into a Sliderule component by adding only the parts of the component
+ * structure that are missing. Optionally the files already in the directory are moved into source.
+ * Returns a description of each change that was made.
+ */
+pub fn init_component(
+    dir: &Path,
+    name: &str,
+    source_license: String,
+    doc_license: String,
+    move_files: bool,
+) -> Result<Vec<String>, String> {
+    if dir.join(".sr").exists() {
+        return Err(String::from(
+            "This directory is already a Sliderule component.",
+        ));
+    }
+
+    // Work out which of the existing files belong in source before anything new is added
+    let mut to_move: Vec<String> = Vec::new();
+    if move_files {
+        let entries =
+            fs::read_dir(dir).map_err(|e| format!("Could not read the directory: {}", e))?;
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            // Hidden files like .git and .gitignore stay where they are
+            if !file_name.starts_with('.')
+                && !component::FILES.contains(&file_name.as_str())
+                && !component::DIRS.contains(&file_name.as_str())
+                && file_name != "node_modules"
+            {
+                to_move.push(file_name);
+            }
+        }
+    }
+
+    let mut changes = add_missing(dir, name, source_license, doc_license)?;
+
+    for file_name in to_move {
+        let target = dir.join("source").join(&file_name);
+
+        if target.exists() {
+            return Err(format!(
+                "Could not move {} into source because source/{} already exists.",
+                file_name, file_name
+            ));
+        }
+
+        fs::rename(dir.join(&file_name), &target)
+            .map_err(|e| format!("Could not move {} into source: {}", file_name, e))?;
+
+        changes.push(format!("Moved {} into source", file_name));
+    }
+
+    Ok(changes)
+}
+
+/*
+ * Adds the parts of the standard component structure that a directory is missing, without
+ * touching anything that is already there. Returns a description of each addition.
+ */
+pub fn add_missing(
+    dir: &Path,
+    name: &str,
+    source_license: String,
+    doc_license: String,
+) -> Result<Vec<String>, String> {
+    // Let the library build a standard component to take the missing parts from
+    let staging_dir = get_temp_dir("init");
+
+    let result = copy_missing(dir, &staging_dir, name, source_license, doc_license);
+
+    // The staging copy is not needed anymore, whether or not the parts could be added
+    fs::remove_dir_all(&staging_dir).ok();
+
+    result
+}
+
+/*
+ * Builds a standard component in a staging directory, and copies the parts of it that a directory
+ * is missing into that directory.
+ */
+fn copy_missing(
+    dir: &Path,
+    staging_dir: &Path,
+    name: &str,
+    source_license: String,
+    doc_license: String,
+) -> Result<Vec<String>, String> {
+    let mut changes: Vec<String> = Vec::new();
+
+    fs::create_dir_all(staging_dir)
+        .map_err(|e| format!("Could not create staging directory: {}", e))?;
+
+    let output =
+        sliderule::create_component(staging_dir, name.to_string(), source_license, doc_license);
+
+    let skeleton_dir = staging_dir.join(name);
+    if !skeleton_dir.exists() {
+        return Err(format!(
+            "Could not create the component structure: {}",
+            output.stderr.join("\n")
+        ));
+    }
+
+    let entries = fs::read_dir(&skeleton_dir)
+        .map_err(|e| format!("Could not read the component structure: {}", e))?;
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let target = dir.join(&file_name);
+
+        // The directory keeps its own repository, if it has one
+        if file_name == ".git" || target.exists() {
+            continue;
+        }
+
+        if entry.path().is_dir() {
+            component::copy_dir(&entry.path(), &target)
+        } else {
+            fs::copy(entry.path(), &target).map(|_| ())
+        }
+        .map_err(|e| format!("Could not add {}: {}", file_name, e))?;
+
+        changes.push(format!("Added {}", file_name));
+    }
+
+    Ok(changes)
+}
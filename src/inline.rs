@@ -0,0 +1,137 @@
+use component;
+use std::path::Path;
+use tools;
+use worktree;
+
+/*
+ * Turns an installed remote component into a local component of the project, optionally bringing
+ * the history of the remote component's repository along with it.
+ */
+pub fn inline_component(project_path: &Path, name: &str, keep_history: bool) -> Result<(), String> {
+    let installed_dir = project_path.join("node_modules").join(name);
+    let local_dir = project_path.join("components").join(name);
+
+    if !installed_dir.exists() {
+        return Err(format!(
+            "The remote component {} is not installed. Run download dependencies first.",
+            name
+        ));
+    }
+    if local_dir.exists() {
+        return Err(format!("A local component named {} already exists.", name));
+    }
+
+    // Everything that could stop the history from being brought in is checked before anything changes
+    let head = if keep_history {
+        check_history(project_path)?;
+
+        Some(
+            tools::git(project_path, &["rev-parse", "HEAD"])?
+                .trim()
+                .to_string(),
+        )
+    } else {
+        None
+    };
+
+    if keep_history {
+        add_with_history(project_path, name)?;
+    } else {
+        component::copy_dir(&installed_dir, &local_dir).map_err(|e| {
+            format!(
+                "Could not copy {} into the components directory: {}",
+                name, e
+            )
+        })?;
+    }
+
+    // sliderule::remove would find the new local copy first, so npm is asked to drop the remote one directly
+    if let Err(e) = tools::npm(project_path, &["uninstall", "--save", name]) {
+        // The project had no other changes, so the commit that brought in the history can be undone
+        if let Some(head) = head {
+            let _ = tools::git(project_path, &["reset", "--hard", &head]);
+        }
+
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/*
+ * Makes sure that the history of a remote component can be brought into the project: the project
+ * needs a repository with no changes that have not been uploaded, and git needs its subtree command.
+ */
+fn check_history(project_path: &Path) -> Result<(), String> {
+    if !project_path.join(".git").exists() {
+        return Err(String::from(
+            "The history of a component can only be kept when the project has been uploaded to a repository.",
+        ));
+    }
+
+    if let Err(e) = tools::git(project_path, &["subtree", "-h"]) {
+        // The help is shown with an error status, so only a missing command is a problem
+        if e.contains("not a git command") {
+            return Err(String::from(
+                "The history of a component can only be kept when git has the subtree command. Install it, or inline the component without --history.",
+            ));
+        }
+    }
+
+    if !worktree::list_uncommitted(project_path).is_empty() {
+        return Err(String::from(
+            "This project has changes that have not been uploaded. Upload them before inlining with --history.",
+        ));
+    }
+
+    Ok(())
+}
+
+/*
+ * Merges the history of a remote component's repository into the project under the components directory.
+ */
+fn add_with_history(project_path: &Path, name: &str) -> Result<(), String> {
+    let url = match component::get_dependencies(project_path)
+        .into_iter()
+        .find(|dependency| dependency.0 == name)
+    {
+        Some(dependency) => component::get_git_url(&dependency.1),
+        None => {
+            return Err(format!(
+                "{} is not listed as a dependency in package.json.",
+                name
+            ))
+        }
+    };
+
+    tools::git(project_path, &["fetch", &url])?;
+
+    // Bring in the exact commit that was installed when the repository still has it
+    let commit = match component::get_locked_commit(project_path, name) {
+        Some(ref commit)
+            if tools::git(
+                project_path,
+                &["cat-file", "-e", &format!("{}^{{commit}}", commit)],
+            )
+            .is_ok() =>
+        {
+            commit.clone()
+        }
+        _ => String::from("FETCH_HEAD"),
+    };
+
+    tools::git(
+        project_path,
+        &[
+            "subtree",
+            "add",
+            "--prefix",
+            &format!("components/{}", name),
+            &commit,
+            "-m",
+            &format!("Inlined remote component {}", name),
+        ],
+    )?;
+
+    Ok(())
+}
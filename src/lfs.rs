@@ -0,0 +1,330 @@
+use get_config_dir;
+use get_temp_dir;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tools;
+
+/*
+ * The large binary files that are kept in Git LFS unless the user lists their own patterns: CAD
+ * models, meshes, gerbers and drill files, and the images in the documentation.
+ */
+const DEFAULT_PATTERNS: &[&str] = &[
+    "*.step",
+    "*.stp",
+    "*.STEP",
+    "*.STP",
+    "*.stl",
+    "*.STL",
+    "*.FCStd",
+    "*.gbr",
+    "*.gtl",
+    "*.gbl",
+    "*.gto",
+    "*.gbo",
+    "*.gts",
+    "*.gbs",
+    "*.gtp",
+    "*.gbp",
+    "*.gko",
+    "*.gm1",
+    "*.drl",
+    "docs/**/*.png",
+    "docs/**/*.jpg",
+    "docs/**/*.jpeg",
+];
+
+/*
+ * Gets the file that lists the patterns of files to keep in Git LFS, one per line.
+ */
+pub fn get_patterns_file() -> PathBuf {
+    get_config_dir().join("lfs_patterns")
+}
+
+/*
+ * Gets the patterns of files to keep in Git LFS, from the user's patterns file if there is one.
+ */
+pub fn get_patterns() -> Vec<String> {
+    match fs::read_to_string(get_patterns_file()) {
+        Ok(contents) => contents
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.to_string())
+            .collect(),
+        Err(_) => DEFAULT_PATTERNS
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect(),
+    }
+}
+
+/*
+ * Tells whether the git-lfs extension is installed.
+ */
+pub fn is_available() -> bool {
+    tools::git(Path::new("."), &["lfs", "version"]).is_ok()
+}
+
+/*
+ * Tells whether a component keeps any of its files in Git LFS.
+ */
+pub fn is_used(component_path: &Path) -> bool {
+    fs::read_to_string(component_path.join(".gitattributes"))
+        .map(|contents| contents.contains("filter=lfs"))
+        .unwrap_or(false)
+}
+
+/*
+ * Adds the Git LFS patterns to a component's .gitattributes file, leaving alone any patterns that
+ * are already there. Returns whether the file was changed.
+ */
+pub fn write_attributes(component_path: &Path) -> Result<bool, String> {
+    let attributes_path = component_path.join(".gitattributes");
+    let mut contents = fs::read_to_string(&attributes_path).unwrap_or_default();
+
+    let existing: Vec<String> = contents
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|pattern| pattern.to_string())
+        .collect();
+
+    let mut changed = false;
+    for pattern in get_patterns() {
+        if existing.contains(&pattern) {
+            continue;
+        }
+
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        contents.push_str(&format!(
+            "{} filter=lfs diff=lfs merge=lfs -text\n",
+            pattern
+        ));
+        changed = true;
+    }
+
+    if changed {
+        fs::write(&attributes_path, contents)
+            .map_err(|e| format!("Could not write {}: {}", attributes_path.display(), e))?;
+    }
+
+    Ok(changed)
+}
+
+/*
+ * Starts keeping large files in Git LFS for a component that does not do so yet, such as one made
+ * with an older version of Sliderule or one adopted with init. The patterns are only written when
+ * some of the component's files match them. Returns whether .gitattributes was changed.
+ */
+pub fn track_large_files(component_path: &Path) -> Result<bool, String> {
+    if is_used(component_path) {
+        return Ok(false);
+    }
+
+    let patterns = get_patterns();
+    let has_large_files = list_files(component_path, component_path)
+        .iter()
+        .any(|file| patterns.iter().any(|pattern| matches(pattern, file)));
+
+    if !has_large_files {
+        return Ok(false);
+    }
+
+    write_attributes(component_path)
+}
+
+/*
+ * Sets up Git LFS for every git command run by Sliderule and by npm, in the same way that the cache
+ * redirects git, so that large files are stored in LFS on upload and fetched on download and add
+ * even when the user has not run git lfs install. In offline mode the large files are left as
+ * pointers, since they cannot be fetched.
+ */
+pub fn configure(offline: bool) {
+    if !is_available() {
+        return;
+    }
+
+    if offline {
+        env::set_var("GIT_LFS_SKIP_SMUDGE", "1");
+    }
+
+    for (key, value) in &[
+        ("filter.lfs.clean", "git-lfs clean -- %f"),
+        ("filter.lfs.smudge", "git-lfs smudge -- %f"),
+        ("filter.lfs.process", "git-lfs filter-process"),
+        ("filter.lfs.required", "true"),
+    ] {
+        add_git_config(key, value);
+    }
+}
+
+/*
+ * Makes sure that the large files of a component reach the LFS store of a remote before the
+ * commits that point at them. Git LFS's pre-push hook uploads them, and stops the push if it
+ * cannot. A component without a repository yet is given a hook that only lasts for its first
+ * upload, since there is nowhere to install one until the upload makes the repository. Returns
+ * the directory of that hook, which finish_push cleans up.
+ */
+pub fn prepare_push(component_path: &Path) -> Result<Option<PathBuf>, String> {
+    if component_path.join(".git").exists() {
+        tools::git(component_path, &["lfs", "install", "--local"])?;
+
+        return Ok(None);
+    }
+
+    let hooks_dir = get_temp_dir("lfs_hooks");
+    let hook_path = hooks_dir.join("pre-push");
+
+    fs::create_dir_all(&hooks_dir)
+        .and_then(|_| fs::write(&hook_path, "#!/bin/sh\ngit lfs pre-push \"$@\"\n"))
+        .map_err(|e| format!("Could not write the Git LFS hook: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Could not write the Git LFS hook: {}", e))?;
+    }
+
+    add_git_config("core.hooksPath", &hooks_dir.to_string_lossy());
+
+    Ok(Some(hooks_dir))
+}
+
+/*
+ * Removes the hook that prepare_push made for a first upload, and installs the usual one into the
+ * repository that the upload made, so that later pushes made with git itself upload large files too.
+ */
+pub fn finish_push(component_path: &Path, hooks_dir: Option<PathBuf>) -> Result<(), String> {
+    let hooks_dir = match hooks_dir {
+        Some(hooks_dir) => hooks_dir,
+        None => return Ok(()),
+    };
+
+    remove_git_config("core.hooksPath");
+    fs::remove_dir_all(&hooks_dir).ok();
+
+    if component_path.join(".git").exists() {
+        tools::git(component_path, &["lfs", "install", "--local"])?;
+    }
+
+    Ok(())
+}
+
+/*
+ * Fetches the large files of a component that are still pointers, such as ones that were
+ * downloaded before Git LFS was installed.
+ */
+pub fn pull(component_path: &Path, remote_name: &str) -> Result<(), String> {
+    tools::git(component_path, &["lfs", "pull", remote_name]).map(|_| ())
+}
+
+/*
+ * Passes a setting to every git process started after this, including the ones started by npm.
+ */
+fn add_git_config(key: &str, value: &str) {
+    let count = env::var("GIT_CONFIG_COUNT")
+        .ok()
+        .and_then(|count| count.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    env::set_var(format!("GIT_CONFIG_KEY_{}", count), key);
+    env::set_var(format!("GIT_CONFIG_VALUE_{}", count), value);
+    env::set_var("GIT_CONFIG_COUNT", (count + 1).to_string());
+}
+
+/*
+ * Stops passing a setting to git processes, when it was the last one that add_git_config passed.
+ */
+fn remove_git_config(key: &str) {
+    let count = env::var("GIT_CONFIG_COUNT")
+        .ok()
+        .and_then(|count| count.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    if count == 0 || env::var(format!("GIT_CONFIG_KEY_{}", count - 1)).ok() != Some(key.to_string())
+    {
+        return;
+    }
+
+    env::remove_var(format!("GIT_CONFIG_KEY_{}", count - 1));
+    env::remove_var(format!("GIT_CONFIG_VALUE_{}", count - 1));
+    env::set_var("GIT_CONFIG_COUNT", (count - 1).to_string());
+}
+
+/*
+ * Lists the files in a directory, at any depth, as paths relative to the component with forward
+ * slashes. Git's files and installed components are left out, since they are not uploaded.
+ */
+fn list_files(component_path: &Path, dir: &Path) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return files,
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if file_name == ".git" || file_name == "node_modules" {
+            continue;
+        }
+
+        if path.is_dir() {
+            files.extend(list_files(component_path, &path));
+        } else {
+            files.push(
+                path.strip_prefix(component_path)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+            );
+        }
+    }
+
+    files
+}
+
+/*
+ * Tells whether a file matches a .gitattributes pattern. Patterns without a slash match the file's
+ * name in any directory. A star matches within one directory, and a double star followed by a
+ * slash matches any number of directories.
+ */
+fn matches(pattern: &str, file: &str) -> bool {
+    if !pattern.contains('/') {
+        let name = file.rsplit('/').next().unwrap_or(file);
+
+        return matches_glob(pattern.as_bytes(), name.as_bytes());
+    }
+
+    matches_glob(pattern.trim_start_matches('/').as_bytes(), file.as_bytes())
+}
+
+/*
+ * Matches a path against a glob, one character of the pattern at a time.
+ */
+fn matches_glob(pattern: &[u8], path: &[u8]) -> bool {
+    if pattern.starts_with(b"**/") {
+        return matches_glob(&pattern[3..], path)
+            || match path.iter().position(|&c| c == b'/') {
+                Some(index) => matches_glob(pattern, &path[index + 1..]),
+                None => false,
+            };
+    }
+
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            matches_glob(&pattern[1..], path)
+                || (!path.is_empty() && path[0] != b'/' && matches_glob(pattern, &path[1..]))
+        }
+        (Some(b'?'), Some(&c)) => c != b'/' && matches_glob(&pattern[1..], &path[1..]),
+        (Some(&p), Some(&c)) => p == c && matches_glob(&pattern[1..], &path[1..]),
+        _ => false,
+    }
+}
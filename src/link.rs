@@ -0,0 +1,139 @@
+use component;
+use get_cwd;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/*
+ * Replaces an installed remote component with a link to a checkout of it that can be edited, so
+ * that changes to the component show up in the project straight away.
+ */
+pub fn link(project_path: &Path, name: &str, checkout: &str) -> Result<(), String> {
+    if !component::get_dependencies(project_path)
+        .iter()
+        .any(|dependency| dependency.0 == name)
+    {
+        return Err(format!(
+            "{} is not a remote component of this project. Only remote components can be linked.",
+            name
+        ));
+    }
+
+    let target = get_cwd()
+        .join(checkout)
+        .canonicalize()
+        .map_err(|e| format!("Could not find {}: {}", checkout, e))?;
+
+    // The checkout has to be the same component, or the project would quietly use something else
+    match component::get_name(&target) {
+        Some(ref target_name) if target_name == name => {}
+        Some(target_name) => {
+            return Err(format!(
+                "{} holds the component {}, not {}.",
+                checkout, target_name, name
+            ))
+        }
+        None => {
+            return Err(format!(
+                "{} is not a Sliderule component. It needs a package.json file with a name.",
+                checkout
+            ))
+        }
+    }
+
+    let installed_path = project_path.join("node_modules").join(name);
+
+    // The installed copy can always be installed again by unlink
+    component::remove_path(&installed_path)
+        .map_err(|e| format!("Could not remove the installed {}: {}", name, e))?;
+
+    fs::create_dir_all(project_path.join("node_modules"))
+        .map_err(|e| format!("Could not create node_modules: {}", e))?;
+
+    make_link(&target, &installed_path)
+        .map_err(|e| format!("Could not link {} to {}: {}", name, target.display(), e))
+}
+
+/*
+ * Removes the link to a checkout of a remote component. The component still has to be installed
+ * again from its repository afterwards.
+ */
+pub fn unlink(project_path: &Path, name: &str) -> Result<(), String> {
+    let installed_path = project_path.join("node_modules").join(name);
+
+    if !component::is_link(&installed_path) {
+        return Err(format!("{} is not linked.", name));
+    }
+
+    component::remove_path(&installed_path)
+        .map_err(|e| format!("Could not remove the link to {}: {}", name, e))
+}
+
+/*
+ * Lists the remote components that are linked to checkouts, along with where each checkout is.
+ */
+pub fn list(project_path: &Path) -> Vec<(String, PathBuf)> {
+    let mut links: Vec<(String, PathBuf)> = Vec::new();
+
+    for (name, location) in component::get_dependencies(project_path) {
+        // npm links components that are installed from a directory, such as vendored ones, itself
+        if location.starts_with("file:") {
+            continue;
+        }
+
+        let installed_path = project_path.join("node_modules").join(&name);
+
+        if component::is_link(&installed_path) {
+            if let Ok(target) = fs::read_link(&installed_path) {
+                links.push((name, target));
+            }
+        }
+    }
+
+    links
+}
+
+/*
+ * Puts back links that npm replaced with installed copies while it was updating dependencies.
+ * Returns any errors that happen along the way.
+ */
+pub fn relink(project_path: &Path, links: &[(String, PathBuf)]) -> Vec<String> {
+    let mut errors: Vec<String> = Vec::new();
+
+    for (name, target) in links {
+        let installed_path = project_path.join("node_modules").join(name);
+
+        if component::is_link(&installed_path) {
+            continue;
+        }
+
+        if let Err(e) =
+            component::remove_path(&installed_path).and_then(|_| make_link(target, &installed_path))
+        {
+            errors.push(format!(
+                "Could not link {} to {} again: {}",
+                name,
+                target.display(),
+                e
+            ));
+        }
+    }
+
+    errors
+}
+
+/*
+ * Makes a link to a directory.
+ */
+#[cfg(unix)]
+fn make_link(target: &Path, link_path: &Path) -> io::Result<()> {
+    ::std::os::unix::fs::symlink(target, link_path)
+}
+
+/*
+ * Windows only allows directory links for administrators, or when developer mode is turned on.
+ */
+#[cfg(windows)]
+fn make_link(target: &Path, link_path: &Path) -> io::Result<()> {
+    ::std::os::windows::fs::symlink_dir(target, link_path)
+}
@@ -2,12 +2,41 @@ extern crate argparse;
 extern crate rpassword;
 extern crate sliderule;
 
+mod branch;
+mod cache;
+mod check;
+mod checkout;
+mod component;
+mod conflicts;
+mod copy;
+mod doctor;
+mod dry_run;
+mod init;
+mod inline;
+mod lfs;
+mod link;
+mod mirror;
+mod nested;
+mod provision;
+mod remote;
+mod rename;
+mod revert;
+mod source;
+mod template;
+mod tools;
+mod transaction;
+mod trash;
+mod vendor;
+mod worktree;
+
 use argparse::{ArgumentParser, List, Store, StoreTrue};
 use sliderule::SROutput;
 use std::env;
 use std::io;
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use transaction::Transaction;
 
 fn main() {
     let _version_num = "0.3.1";
@@ -21,19 +50,60 @@ fn main() {
     let mut url = String::new();
     let mut yes_mode_active = false;
     let mut verbose = false;
+    let mut offline = false;
+    let mut all = false;
+    let mut history = false;
+    let mut sync = false;
+    let mut template = String::new();
+    let mut project = false;
+    let mut name_option = String::new();
+    let mut move_files = false;
+    let mut recursive = false;
+    let mut fix = false;
+    let mut dry_run = false;
+    let mut ours = false;
+    let mut theirs = false;
+    let mut to = String::new();
+    let mut into = String::new();
+    let mut create_remote = false;
+    let mut remote_name = String::new();
+    let mut rewrite_urls = false;
     let mut version = false;
 
     // Some items for the command line help interface
     let app_description = "Tool to manage Sliderule projects.";
-    let cmd_description = "Sliderule command to run: [create | download | upload | add | remove | refactor | licenses | login]";
+    let cmd_description = "Sliderule command to run: [create | download | upload | add | remove | refactor | licenses | login | vendor | inline | rename | move | copy | template | init | check | doctor | restore | trash | conflicts | sync | link | unlink | branch | status | revert | checkout | remote | mirror]";
     let args_description = "Arguments to Sliderule commands:
-                            create [name],
-                            download [all | dependencies | component_url],
-                            add [remote_component_url],
+                            create [name] [--template name | path | url],
+                            download [all | dependencies | component_url] [--remote name],
+                            upload [-m message] [--recursive] [--create-remote] [--remote name],
+                            add [remote_component_url | local_path | archive],
                             remove [name],
-                            refactor [name],
+                            refactor [name] [--create-remote],
                             licenses [change | list],
-                            changes [list]";
+                            login [--remote name],
+                            changes [list | history],
+                            vendor [name | --all] [--sync],
+                            inline [name],
+                            rename [old_name] [new_name],
+                            move [name] [new_parent],
+                            copy [name] [new_name],
+                            template [list | add [path | url] [name] | remove [name]],
+                            init [--name name] [--move-files],
+                            check [--recursive] [--fix],
+                            doctor [env],
+                            restore [name],
+                            trash [list | empty],
+                            conflicts [list | resolve [file] [--ours | --theirs]],
+                            sync [-m message],
+                            link [name] [path],
+                            unlink [name],
+                            branch [create [name] | list | switch [name] | merge [name] | delete [name]],
+                            status,
+                            revert [--to upload_id | tag | date] [name],
+                            checkout [tag | version] [--into directory],
+                            remote [list | add [name] [url] | remove [name] | set-url [name] [url]],
+                            mirror [base_url] [--all] [--rewrite-urls] [--create-remote] [--remote name]";
 
     // Parse the command line arguments
     {
@@ -73,6 +143,96 @@ fn main() {
             StoreTrue,
             "Gives verbose output, helps with debugging why a command did not work.",
         );
+        ap.refer(&mut offline).add_option(
+            &["--offline"],
+            StoreTrue,
+            "Uses only components that are already in the local cache, without accessing the network.",
+        );
+        ap.refer(&mut all).add_option(
+            &["--all"],
+            StoreTrue,
+            "Applies the command to all components it can work with.",
+        );
+        ap.refer(&mut history).add_option(
+            &["--history"],
+            StoreTrue,
+            "Keeps the repository history of a component that is being inlined.",
+        );
+        ap.refer(&mut sync).add_option(
+            &["--sync"],
+            StoreTrue,
+            "Updates vendored components from the repositories they were vendored from.",
+        );
+        ap.refer(&mut template).add_option(
+            &["--template"],
+            Store,
+            "The name, path or URL of a template to create a component from.",
+        );
+        ap.refer(&mut project).add_option(
+            &["--project"],
+            StoreTrue,
+            "Manages the templates of the current project instead of the user's templates.",
+        );
+        ap.refer(&mut name_option).add_option(
+            &["--name"],
+            Store,
+            "The name to give a directory that is being initialized as a component.",
+        );
+        ap.refer(&mut move_files).add_option(
+            &["--move-files"],
+            StoreTrue,
+            "Moves the files already in a directory into source when initializing it as a component.",
+        );
+        ap.refer(&mut recursive).add_option(
+            &["--recursive"],
+            StoreTrue,
+            "Also applies the command to the components inside the current component.",
+        );
+        ap.refer(&mut fix).add_option(
+            &["--fix"],
+            StoreTrue,
+            "Repairs the problems that check finds, where that can be done automatically.",
+        );
+        ap.refer(&mut dry_run).add_option(
+            &["--dry-run"],
+            StoreTrue,
+            "Shows the file, git and npm changes a command would make without making them.",
+        );
+        ap.refer(&mut ours).add_option(
+            &["--ours"],
+            StoreTrue,
+            "Keeps the local changes when resolving a merge conflict.",
+        );
+        ap.refer(&mut theirs).add_option(
+            &["--theirs"],
+            StoreTrue,
+            "Keeps the downloaded changes when resolving a merge conflict.",
+        );
+        ap.refer(&mut to).add_option(
+            &["--to"],
+            Store,
+            "The upload ID, tag or YYYY-MM-DD date of the upload to revert to.",
+        );
+        ap.refer(&mut into).add_option(
+            &["--into"],
+            Store,
+            "The directory to check a release out into.",
+        );
+        ap.refer(&mut create_remote).add_option(
+            &["--create-remote"],
+            StoreTrue,
+            "Creates the remote repository to upload to if it does not exist yet.",
+        );
+        ap.refer(&mut remote_name).add_option(
+            &["--remote"],
+            Store,
+            "The name of the remote to upload to, download from or log in to.",
+        );
+        ap.refer(&mut rewrite_urls).add_option(
+            &["--rewrite-urls"],
+            StoreTrue,
+            "Points the remote components in package.json at their mirrored copies.",
+        );
         ap.refer(&mut version)
             .add_option(&["-V"], StoreTrue, "Outputs the version information.");
         ap.parse_args_or_exit();
@@ -87,6 +247,26 @@ fn main() {
         std::process::exit(1);
     }
 
+    // Fetched components are cached, and offline mode makes git and npm use only that cache
+    let fetching = [
+        "add", "download", "sync", "checkout", "restore", "unlink", "branch", "vendor",
+    ]
+    .contains(&command.as_str());
+    cache::configure(offline, fetching);
+    lfs::configure(offline);
+
+    if offline && !tools::git_reads_env_config() {
+        println!(
+            "WARNING: git 2.31 or newer is needed to use the cached components in offline mode."
+        );
+    }
+
+    // Commands that work with a remote use the default one unless another is named
+    let remote_is_named = !remote_name.is_empty();
+    if !remote_is_named {
+        remote_name = String::from(remote::DEFAULT_REMOTE);
+    }
+
     // Handle the command line arguments
     if command == "create" {
         let name = &args[0];
@@ -105,9 +285,79 @@ fn main() {
             }
         }
 
+        // Find the template before anything is created so that a bad template does not leave a half made component
+        let mut template_dir = None;
+        if !template.is_empty() && (!dry_run || !template::is_url(&template)) {
+            match template::resolve(&template) {
+                Ok(dir) => template_dir = Some(dir),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        }
+
+        // New components go in the components directory when they are created inside of another component
+        let component_path = if get_cwd().join(".sr").exists() {
+            get_cwd().join("components").join(name)
+        } else {
+            get_cwd().join(name)
+        };
+
+        if dry_run {
+            // Templates from repositories are not downloaded just to show what would be done
+            let template_source = match template_dir {
+                Some(ref dir) => Some(dir.dir.to_string_lossy().to_string()),
+                None if !template.is_empty() => Some(template.to_string()),
+                None => None,
+            };
+
+            print_plan(dry_run::plan_create(
+                &get_cwd(),
+                &component_path,
+                template_source.as_deref(),
+            ));
+            return;
+        }
+
+        let values = template::get_values(name, &src_license, &docs_license);
+
+        // A component created outside of a project does not change any other files
+        let transaction = start_transaction(if get_cwd().join(".sr").exists() {
+            Transaction::begin(&get_cwd(), name)
+        } else {
+            Transaction::begin_with(&get_cwd(), std::slice::from_ref(&component_path))
+        });
+
         let output =
             sliderule::create_component(&get_cwd(), name.to_string(), src_license, docs_license);
 
+        let created = !transaction::has_failed(&output);
+
+        // Lay the template over the standard component structure, unless there is no component to lay it over
+        if let Some(dir) = template_dir {
+            let result = if created {
+                template::apply(&dir.dir, &component_path, &values)
+            } else {
+                Ok(())
+            };
+
+            dir.clean_up();
+
+            if let Err(e) = result {
+                eprintln!("ERROR: {}", e);
+                rollback_transaction(transaction, "creation");
+                std::process::exit(2);
+            }
+        }
+
+        // Large CAD and fabrication files are kept out of the repository itself from the start
+        if created && component_path.exists() {
+            if let Err(e) = lfs::write_attributes(&component_path) {
+                println!("WARNING: {}", e);
+            }
+        }
+
         // Show extra output only when the user requests it
         if verbose {
             print_stdout(&output);
@@ -119,11 +369,96 @@ fn main() {
         if !output.stderr.is_empty() {
             print_stderr(&output);
         }
+
+        finish_transaction(transaction, &output, "creation");
     } else if command == "add" {
-        // The user is expected to have provided a URL of a remote component that can be downloaded
-        let url = &args[0];
+        // The user is expected to have provided a URL of a remote component, or the location of one on the local filesystem
+        let mut url = args[0].to_string();
+
+        // Local components are checked before they are installed, since they have not come from a known repository
+        let mut local_source = None;
+        if source::is_local_source(&url) {
+            match source::prepare(&url) {
+                Ok(source) => {
+                    url = source.path.to_string_lossy().to_string();
+                    local_source = Some(source);
+                }
+                Err(problems) => {
+                    eprintln!("ERROR: {} is not a valid Sliderule component:", url);
+                    for problem in problems {
+                        eprintln!("    {}", problem);
+                    }
+                    std::process::exit(4);
+                }
+            }
+        }
+
+        let is_local = source::is_local_source(&url);
+
+        // Without the network, a remote component can only come from the cache
+        if offline && !is_local && !cache::is_cached(&url) {
+            eprintln!(
+                "ERROR: {} is not in the local cache and cannot be added in offline mode.",
+                url
+            );
+            std::process::exit(5);
+        }
+
+        // The name a remote component is installed under usually matches its repository name
+        let name = if is_local {
+            component::get_name(Path::new(&url)).unwrap_or_default()
+        } else {
+            url.trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or("")
+                .trim_end_matches(".git")
+                .to_string()
+        };
+        if dry_run {
+            let cache_dir = cache::get_cache_dir();
+
+            print_plan(dry_run::plan_add(
+                &get_cwd(),
+                &url,
+                &name,
+                if offline || is_local {
+                    None
+                } else {
+                    Some(cache_dir.as_path())
+                },
+            ));
+
+            if let Some(source) = local_source {
+                source.clean_up();
+            }
+            return;
+        }
+
+        let transaction = start_transaction(Transaction::begin(&get_cwd(), &name));
+
+        let links = link::list(&get_cwd());
+
+        let output = sliderule::add_remote_component(
+            &get_cwd(),
+            &url,
+            Some(
+                cache::get_cache_dir()
+                    .join("npm")
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+        );
+
+        // npm replaces linked components with installed copies when it installs anything
+        for error in link::relink(&get_cwd(), &links) {
+            println!("WARNING: {}", error);
+        }
 
-        let output = sliderule::add_remote_component(&get_cwd(), &url, None);
+        // npm has its own copy of a local component once it is installed
+        if let Some(source) = local_source {
+            source.clean_up();
+        }
 
         // Show extra output only when the user requests it
         if verbose {
@@ -136,11 +471,37 @@ fn main() {
         if !output.stderr.is_empty() {
             print_stderr(&output);
         }
+
+        finish_transaction(transaction, &output, "add");
+
+        // Keep a copy of the component so that it can be added again without the network
+        if !offline && !is_local {
+            cache_component(&url);
+        }
     } else if command == "download" {
         let subcommand = &args[0];
 
+        if dry_run {
+            print_plan(dry_run::plan_download(
+                &get_cwd(),
+                &remote_name,
+                subcommand,
+                offline,
+            ));
+            return;
+        }
+
         // Check to see if we have a URL
         if subcommand.contains("/") {
+            // Without the network, the component can only come from the cache
+            if offline && !cache::is_cached(subcommand) {
+                eprintln!(
+                    "ERROR: {} is not in the local cache and cannot be downloaded in offline mode.",
+                    subcommand
+                );
+                std::process::exit(5);
+            }
+
             // git clone here and warn the user that what they're downloading is possibly read only
             let output = sliderule::download_component(&get_cwd(), subcommand);
 
@@ -156,24 +517,65 @@ fn main() {
                 print_stderr(&output);
             }
 
-            println!("Unless you have write access to the downloaded repository, this copy will be read-only.")
-        } else if subcommand == "all" {
-            let output = sliderule::update_local_component(&get_cwd());
+            println!("Unless you have write access to the downloaded repository, this copy will be read-only.");
 
-            // Show extra output only when the user requests it
-            if verbose {
-                print_stdout(&output);
-            } else {
-                println!("Component download finished.");
+            // Keep a copy of the component so that it can be downloaded again without the network
+            if !offline {
+                cache_component(subcommand);
             }
+        } else if subcommand == "all" {
+            // The component's own repository is not cached, so its changes can only come from the network
+            if offline {
+                println!("Offline mode active, skipping the download of component changes.");
+            } else {
+                check_remote(&get_cwd(), &remote_name);
+
+                // Downloading on top of changes that have not been uploaded can tangle the two together
+                let stashed = match ask_about_uncommitted(yes_mode_active) {
+                    Some(stashed) => stashed,
+                    None => {
+                        println!("Aborting component download.");
+                        return;
+                    }
+                };
+
+                let output = download_changes(&get_cwd(), &remote_name);
+
+                // Show extra output only when the user requests it
+                if verbose {
+                    print_stdout(&output);
+                } else {
+                    println!("Component download finished.");
+                }
 
-            // Show error information when it happens, whether the user has requested verbose output or not
-            if !output.stderr.is_empty() {
-                print_stderr(&output);
+                // Conflicts are explained file by file, since git's own output is hard to act on
+                if report_conflicts("The downloaded changes") {
+                    if stashed {
+                        println!("Your own changes were set aside with git stash and have not been put back. Run git stash pop once the conflicts are resolved.");
+                    }
+                    std::process::exit(8);
+                }
+
+                // Show error information when it happens, whether the user has requested verbose output or not
+                if !output.stderr.is_empty() {
+                    print_stderr(&output);
+                }
+
+                if stashed {
+                    if let Err(e) = worktree::unstash(&get_cwd()) {
+                        if !report_conflicts("The downloaded changes") {
+                            eprintln!("ERROR: Could not put your changes back: {}", e);
+                        }
+                        println!(
+                            "Your changes are still saved, and can be seen with git stash list."
+                        );
+                        std::process::exit(8);
+                    }
+                }
             }
 
             // Just have npm update the entire project, not install a specific package
-            let output = sliderule::update_dependencies(&get_cwd());
+            let output = update_dependencies();
 
             // Show extra output only when the user requests it
             if verbose {
@@ -186,9 +588,14 @@ fn main() {
             if !output.stderr.is_empty() {
                 print_stderr(&output);
             }
+
+            // Keep copies of the dependencies so that they can be installed again without the network
+            if !offline {
+                cache_dependencies();
+            }
         } else if subcommand == "dependencies" {
             // Just have npm update the entire project, not install a specific package
-            let output = sliderule::update_dependencies(&get_cwd());
+            let output = update_dependencies();
 
             // Show extra output only when the user requests it
             if verbose {
@@ -201,11 +608,55 @@ fn main() {
             if !output.stderr.is_empty() {
                 print_stderr(&output);
             }
+
+            // Keep copies of the dependencies so that they can be installed again without the network
+            if !offline {
+                cache_dependencies();
+            }
         } else {
             eprintln!("ERROR: Subcommand of download not recognized.");
             std::process::exit(3);
         }
     } else if command == "upload" {
+        // Nothing is uploaded, so there is no need to ask the user for a message or URL
+        if dry_run {
+            if message.is_empty() {
+                message = String::from("<message>");
+            }
+            if url.is_empty() && !Path::new(".git").exists() {
+                url = String::from("<url>");
+            }
+
+            let mut steps = Vec::new();
+            if recursive {
+                match dry_run::plan_upload_children(&get_cwd(), &remote_name, &message) {
+                    Ok(child_steps) => steps.extend(child_steps),
+                    Err(child_steps) => {
+                        steps.extend(child_steps);
+                        print_plan(steps);
+                        return;
+                    }
+                }
+            }
+            if create_remote {
+                steps.push(format!(
+                    "create the repository {} if it does not exist",
+                    url
+                ));
+            }
+            steps.extend(dry_run::plan_upload(
+                &get_cwd(),
+                &remote_name,
+                &message,
+                &url,
+            ));
+
+            print_plan(steps);
+            return;
+        }
+
+        check_remote(&get_cwd(), &remote_name);
+
         if message.is_empty() {
             // Get the upload message from the user to mark these changes with
             println!("Message to attach to these project changes:");
@@ -221,7 +672,11 @@ fn main() {
 
         // Make sure this project has already been initialized as a repository
         if !Path::new(".git").exists() && url.is_empty() {
-            println!("This project has not been initialized with a repository yet. Enter a URL of an existing repository to upload this component to:");
+            if create_remote {
+                println!("This project has not been initialized with a repository yet. Enter the URL of the repository to create and upload this component to:");
+            } else {
+                println!("This project has not been initialized with a repository yet. Enter a URL of an existing repository to upload this component to:");
+            }
 
             io::stdin()
                 .read_line(&mut url)
@@ -242,7 +697,75 @@ fn main() {
             pass = Some(userinfo.1.trim().to_string());
         }
 
-        let output = sliderule::upload_component(&get_cwd(), message, url, user, pass);
+        // Child components go first so that the references to them point at what was uploaded
+        if recursive {
+            for child in nested::list_child_repos(&get_cwd()) {
+                if !nested::has_changes(&child) {
+                    continue;
+                }
+
+                let location = child.strip_prefix(get_cwd()).unwrap().display().to_string();
+
+                if remote::get_url(&child, &remote_name).is_err() {
+                    // A repository that was only made locally has nowhere to be uploaded to yet
+                    if remote_name == remote::DEFAULT_REMOTE {
+                        println!(
+                            "WARNING: {} has not been uploaded to a repository yet, so it has been skipped. Run upload inside of it to give it one.",
+                            location
+                        );
+                        continue;
+                    }
+
+                    eprintln!(
+                        "ERROR: {} has no remote named {}, so the components that use it have not been uploaded.",
+                        location, remote_name
+                    );
+                    std::process::exit(2);
+                }
+
+                let output = upload_changes(
+                    &child,
+                    &remote_name,
+                    message.to_string(),
+                    String::new(),
+                    None,
+                    None,
+                );
+
+                if verbose {
+                    print_stdout(&output);
+                }
+
+                if transaction::has_failed(&output) {
+                    print_stderr(&output);
+                    eprintln!(
+                        "ERROR: Could not upload {}, so the components that use it have not been uploaded.",
+                        location
+                    );
+                    std::process::exit(2);
+                }
+
+                println!("Component {} upload finished.", location);
+
+                if let Err(e) = nested::update_references(&child) {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        }
+
+        if create_remote {
+            // Components that were uploaded before already know where their repository is
+            let target = if url.is_empty() {
+                remote::get_url(&get_cwd(), &remote_name).unwrap_or_default()
+            } else {
+                url.clone()
+            };
+
+            create_remote_repository(&target);
+        }
+
+        let output = upload_changes(&get_cwd(), &remote_name, message, url, user, pass);
 
         // Show extra output only when the user requests it
         if verbose {
@@ -258,6 +781,11 @@ fn main() {
     } else if command == "remove" {
         let name = &args[0];
 
+        if dry_run {
+            print_plan(dry_run::plan_remove(&get_cwd(), name));
+            return;
+        }
+
         if !yes_mode_active {
             let mut answer = String::new();
 
@@ -278,9 +806,31 @@ fn main() {
             }
         }
 
+        let transaction = start_transaction(Transaction::begin(&get_cwd(), name));
+
+        // Keep a copy of the component so that the removal can be undone with restore
+        let trash_id = match trash::trash_component(&get_cwd(), name) {
+            Ok(id) => id,
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        };
+
+        // The component being removed is not linked again afterwards
+        let links: Vec<(String, PathBuf)> = link::list(&get_cwd())
+            .into_iter()
+            .filter(|link| link.0 != *name)
+            .collect();
+
         // Deletes a local component's directory, or npm uninstalls a remote component
         let output = sliderule::remove(&get_cwd(), name);
 
+        // npm replaces linked components with installed copies when it uninstalls anything
+        for error in link::relink(&get_cwd(), &links) {
+            println!("WARNING: {}", error);
+        }
+
         // Show extra output only when the user requests it
         if verbose {
             print_stdout(&output);
@@ -292,13 +842,30 @@ fn main() {
         if !output.stderr.is_empty() {
             print_stderr(&output);
         }
+
+        // The component is still in the project, so it does not belong in the trash
+        if transaction::has_failed(&output) {
+            if let Some(id) = trash_id {
+                trash::discard(&get_cwd(), &id);
+            }
+        }
+
+        finish_transaction(transaction, &output, "remove");
     } else if command == "refactor" {
         let name = &args[0];
 
         let mut userinfo = (String::new(), String::new());
 
         if url.is_empty() {
-            println!("Please enter the URL of an existing repository to upload the component to:");
+            if create_remote {
+                println!(
+                    "Please enter the URL of the repository to create and upload the component to:"
+                );
+            } else {
+                println!(
+                    "Please enter the URL of an existing repository to upload the component to:"
+                );
+            }
 
             io::stdin()
                 .read_line(&mut url)
@@ -307,6 +874,24 @@ fn main() {
             url = url.trim().to_string();
         }
 
+        if dry_run {
+            let mut steps = Vec::new();
+            if create_remote {
+                steps.push(format!(
+                    "create the repository {} if it does not exist",
+                    url
+                ));
+            }
+            steps.extend(dry_run::plan_refactor(&get_cwd(), name, &url));
+
+            print_plan(steps);
+            return;
+        }
+
+        if create_remote {
+            create_remote_repository(&url);
+        }
+
         // Check to see if there needs to be a username and password set for this
         if url.contains("https") {
             userinfo = get_https_user_info();
@@ -319,6 +904,8 @@ fn main() {
             pass = Some(userinfo.1.trim().to_string());
         }
 
+        let transaction = start_transaction(Transaction::begin(&get_cwd(), name));
+
         // Convert the local component into a remote component
         let output = sliderule::refactor(&get_cwd(), name.to_string(), url, user, pass);
 
@@ -333,6 +920,8 @@ fn main() {
         if !output.stderr.is_empty() {
             print_stderr(&output);
         }
+
+        finish_transaction(transaction, &output, "refactor");
     } else if command == "licenses" {
         let subcommand = &args[0];
         let mut licenses = (String::new(), String::new());
@@ -351,6 +940,15 @@ fn main() {
                 docs_license = licenses.1;
             }
 
+            if dry_run {
+                print_plan(dry_run::plan_change_licenses(
+                    &get_cwd(),
+                    &src_license,
+                    &docs_license,
+                ));
+                return;
+            }
+
             let output = sliderule::change_licenses(&get_cwd(), src_license, docs_license);
 
             // Show extra output only when the user requests it
@@ -373,6 +971,17 @@ fn main() {
             std::process::exit(1);
         }
     } else if command == "login" {
+        // Other remotes keep their URL unless a new one is given
+        if url.is_empty() && remote_name != remote::DEFAULT_REMOTE {
+            url = match remote::get_url(&get_cwd(), &remote_name) {
+                Ok(url) => url,
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            };
+        }
+
         // Make sure this project has already been initialized as a repository
         if url.is_empty() {
             println!(
@@ -406,6 +1015,22 @@ fn main() {
             std::process::exit(2);
         }
 
+        // Sliderule only logs in to the default remote
+        if remote_name != remote::DEFAULT_REMOTE {
+            match remote::login(&get_cwd(), &remote_name, &url, user, pass) {
+                Ok(_) => println!(
+                    "Finished setting username and password for remote repository {}.",
+                    remote_name
+                ),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+
+            return;
+        }
+
         // Change/add the login information for the user
         let output = sliderule::remote_login(&get_cwd(), Some(url), user, pass);
 
@@ -438,73 +1063,1375 @@ fn main() {
                     println!("{}", line);
                 }
             }
+        } else if subcommand == "history" {
+            let uploads = match revert::history(&get_cwd()) {
+                Ok(uploads) => uploads,
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            };
+
+            // The IDs and tags listed here are what revert --to accepts
+            for upload in uploads {
+                if upload.tags.is_empty() {
+                    println!("{} {} {}", upload.id, upload.date, upload.message);
+                } else {
+                    println!(
+                        "{} {} {} (tag {})",
+                        upload.id,
+                        upload.date,
+                        upload.message,
+                        upload.tags.join(", ")
+                    );
+                }
+            }
+        } else {
+            eprintln!("changes subcommand not understood: {}", subcommand);
+            std::process::exit(1);
         }
-    }
+    } else if command == "vendor" {
+        let names = if all && sync {
+            vendor::list_vendored(&get_cwd())
+        } else if all {
+            vendor::list_installed(&get_cwd())
+        } else {
+            args.clone()
+        };
 
-    // The user has to supply a command, and it needs to be recognized
-    if command.is_empty() {
-        println!("Please supply a command. Run with -h to see the options.");
-        std::process::exit(1);
-    }
-}
+        if names.is_empty() && all {
+            if sync {
+                println!("There are no vendored components to sync.");
+            } else {
+                println!("There are no installed remote components left to vendor.");
+            }
+            return;
+        } else if names.is_empty() {
+            eprintln!(
+                "ERROR: Please supply the name of a remote component to vendor, or use --all."
+            );
+            std::process::exit(3);
+        }
 
-/*
- * Prompts the user for an https username and a password, with a warning that doing so is a security concern.
- */
-fn get_https_user_info() -> (String, String) {
-    let mut username = String::new();
-    let password: String;
+        for name in names {
+            // Vendored copies are brought up to date from the repositories they came from
+            if sync {
+                match vendor::sync_component(&get_cwd(), &name) {
+                    Ok((old_commit, new_commit)) => {
+                        if old_commit == new_commit {
+                            println!("{} is already up to date at {}.", name, new_commit);
+                        } else {
+                            println!("Synced {} from {} to {}.", name, old_commit, new_commit);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("ERROR: {}", e);
+                        std::process::exit(2);
+                    }
+                }
 
-    println!(
-        "You need to enter a username and password for using an https URL. Please do that now."
-    );
-    println!("WARNING: Using https with sliderule-cli can lead to passwords being stored in plain text. It is better to use ssh.");
-    print!("User: ");
-    io::stdout().flush().ok().expect("Could not flush stdout");
-    io::stdin()
-        .read_line(&mut username)
-        .expect("ERROR: Failed to read username from user");
+                continue;
+            }
 
-    password = rpassword::prompt_password_stdout("Password: ").unwrap();
+            // Copy each remote component into the project so that it no longer depends on npm or its repository
+            match vendor::vendor_component(&get_cwd(), &name) {
+                Ok(_) => {
+                    if verbose {
+                        println!("Vendored {} into vendor/{}.", name, name);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        }
 
-    (username, password)
-}
+        // Have npm install the vendored copies in place of the ones from the repositories
+        let output = update_dependencies();
 
-/*
- * Prints all the lines of standard output to standard output.
- */
-fn print_stdout(output: &SROutput) {
-    for line in &output.stdout {
-        if !line.is_empty() {
-            println!("{}", line);
+        // Show extra output only when the user requests it
+        if verbose {
+            print_stdout(&output);
         }
-    }
-}
 
-/*
- * Prints all the lines of standard output to standard output.
- */
-fn print_stderr(output: &SROutput) {
-    for line in &output.stderr {
-        if !line.is_empty() {
-            println!("{}", line);
+        // Show error information when it happens, whether the user has requested verbose output or not
+        if !output.stderr.is_empty() {
+            print_stderr(&output);
         }
-    }
-}
 
-/*
- * Prompt the user to ask for licenses.
- */
-fn ask_for_licenses(display_anyway: bool) -> (String, String) {
-    let licenses = sliderule::get_licenses(&get_cwd());
-    let default_src_license = licenses.0;
-    let default_docs_lic = licenses.1;
+        if transaction::has_failed(&output) {
+            eprintln!("ERROR: The vendored components could not be installed.");
+            std::process::exit(2);
+        }
 
-    let mut source_license = String::new();
-    let mut doc_license = String::new();
+        println!("Component vendor finished.");
+    } else if command == "inline" {
+        let name = &args[0];
 
-    // Ask the user for their license choice for the source of this component if they haven't specified it on the command line
-    if sliderule::get_level(&get_cwd()) == 0 || display_anyway {
+        let transaction = start_transaction(Transaction::begin(&get_cwd(), name));
+
+        // Convert the remote component into a local component
+        match inline::inline_component(&get_cwd(), name, history) {
+            Ok(_) => {
+                if let Some(transaction) = transaction {
+                    transaction.commit();
+                }
+
+                println!("Component inline finished.");
+            }
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                rollback_transaction(transaction, "inline");
+                std::process::exit(2);
+            }
+        }
+    } else if command == "rename" {
+        if args.len() < 2 {
+            eprintln!("ERROR: Please supply the current and new names of the component.");
+            std::process::exit(3);
+        }
+
+        match rename::rename_component(&get_cwd(), &args[0], &args[1]) {
+            Ok(_) => println!("Component rename finished."),
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        }
+    } else if command == "move" {
+        if args.len() < 2 {
+            eprintln!(
+                "ERROR: Please supply the name of the component and the name of its new parent."
+            );
+            std::process::exit(3);
+        }
+
+        match rename::move_component(&get_cwd(), &args[0], &args[1]) {
+            Ok(_) => println!("Component move finished."),
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        }
+    } else if command == "copy" {
+        if args.len() < 2 {
+            eprintln!(
+                "ERROR: Please supply the name of the component to copy and the name of the copy."
+            );
+            std::process::exit(3);
+        }
+
+        match copy::copy_component(&get_cwd(), &args[0], &args[1]) {
+            Ok(_) => println!("Component copy finished."),
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        }
+    } else if command == "template" {
+        let subcommand = &args[0];
+
+        if subcommand == "list" {
+            let templates = template::list();
+
+            if templates.is_empty() {
+                println!("No templates available.");
+            }
+
+            for (name, scope) in templates {
+                println!("{} ({})", name, scope);
+            }
+        } else if subcommand == "add" {
+            if args.len() < 2 {
+                eprintln!("ERROR: Please supply the directory or URL of the template to add.");
+                std::process::exit(3);
+            }
+
+            // The template is named after its directory or repository unless the user gives it a name
+            let name = if args.len() > 2 {
+                args[2].to_string()
+            } else {
+                template::get_default_name(&args[1])
+            };
+
+            match template::add(&args[1], &name, project) {
+                Ok(_) => println!("Template add finished."),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        } else if subcommand == "remove" {
+            if args.len() < 2 {
+                eprintln!("ERROR: Please supply the name of the template to remove.");
+                std::process::exit(3);
+            }
+
+            match template::remove(&args[1], project) {
+                Ok(_) => println!("Template remove finished."),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        } else {
+            eprintln!("template subcommand not understood: {}", subcommand);
+            std::process::exit(1);
+        }
+    } else if command == "init" {
+        // Default to the name of the directory that is being initialized
+        let name = if name_option.is_empty() {
+            get_cwd()
+                .file_name()
+                .expect("ERROR: Could not get the name of the current directory.")
+                .to_string_lossy()
+                .to_string()
+        } else {
+            name_option.to_string()
+        };
+
+        // Only ask for licenses if they are not specified on the command line
+        if src_license.is_empty() || docs_license.is_empty() {
+            let licenses = ask_for_licenses(false);
+
+            if src_license.is_empty() {
+                src_license = licenses.0;
+            }
+            if docs_license.is_empty() {
+                docs_license = licenses.1;
+            }
+        }
+
+        match init::init_component(&get_cwd(), &name, src_license, docs_license, move_files) {
+            Ok(mut changes) => {
+                // Large files that were already in the directory are kept out of the repository from the start
+                match lfs::track_large_files(&get_cwd()) {
+                    Ok(true) => {
+                        changes.push(String::from("Added the Git LFS patterns to .gitattributes"))
+                    }
+                    Ok(false) => {}
+                    Err(e) => println!("WARNING: {}", e),
+                }
+
+                // Show extra output only when the user requests it
+                if verbose {
+                    for change in changes {
+                        println!("{}", change);
+                    }
+                }
+
+                println!("Component initialization finished.");
+            }
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        }
+    } else if command == "check" {
+        let mut components = vec![(get_cwd(), false)];
+
+        if recursive {
+            for path in check::list_local(&get_cwd()) {
+                components.push((path, true));
+            }
+        }
+
+        let mut problem_count = 0;
+        for (path, is_local) in components {
+            let mut problems = check::check_component(&path, is_local);
+
+            // Repair what can be repaired, then look again to see what is left
+            if fix && problems.iter().any(|problem| problem.fix.is_some()) {
+                for error in check::fix_component(&path, &problems) {
+                    println!("{}", error);
+                }
+
+                problems = check::check_component(&path, is_local);
+            }
+
+            if problems.is_empty() {
+                continue;
+            }
+
+            // Show the component's location relative to where the user is
+            let location = path.strip_prefix(get_cwd()).unwrap().to_path_buf();
+            if location.as_os_str().is_empty() {
+                println!(".:");
+            } else {
+                println!("{}:", location.display());
+            }
+
+            for problem in &problems {
+                if problem.fix.is_some() {
+                    println!("    {} Run check --fix to repair this.", problem.message);
+                } else {
+                    println!("    {}", problem.message);
+                }
+            }
+
+            problem_count += problems.len();
+        }
+
+        if problem_count == 0 {
+            println!("Component check finished, no problems found.");
+        } else {
+            println!(
+                "Component check finished, {} problem(s) found.",
+                problem_count
+            );
+            std::process::exit(6);
+        }
+    } else if command == "doctor" {
+        // Environment checks are the only kind so far, so they are the default
+        let subcommand = if args.is_empty() {
+            "env"
+        } else {
+            args[0].as_str()
+        };
+
+        if subcommand == "env" {
+            let checks = doctor::check_env(&get_cwd(), offline);
+
+            let mut failure_count = 0;
+            for check in &checks {
+                let label = match check.status {
+                    doctor::Status::Pass => "PASS",
+                    doctor::Status::Fail => {
+                        failure_count += 1;
+                        "FAIL"
+                    }
+                    doctor::Status::Skip => "SKIP",
+                };
+
+                println!("[{}] {}: {}", label, check.name, check.detail);
+            }
+
+            if failure_count == 0 {
+                println!("Environment check finished, no problems found.");
+            } else {
+                println!(
+                    "Environment check finished, {} problem(s) found.",
+                    failure_count
+                );
+                std::process::exit(7);
+            }
+        } else {
+            eprintln!("doctor subcommand not understood: {}", subcommand);
+            std::process::exit(1);
+        }
+    } else if command == "restore" {
+        if args.is_empty() {
+            eprintln!("ERROR: Please supply the name of the component to restore.");
+            std::process::exit(3);
+        }
+
+        match trash::restore(&get_cwd(), &args[0]) {
+            Ok(_) => println!("Component restore finished."),
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        }
+    } else if command == "trash" {
+        let subcommand = &args[0];
+
+        if subcommand == "list" {
+            let entries = trash::list(&get_cwd());
+
+            if entries.is_empty() {
+                println!("The trash is empty.");
+            }
+
+            for entry in entries {
+                println!(
+                    "{} ({}, removed {})",
+                    entry.name,
+                    entry.kind,
+                    component::get_date(entry.removed)
+                );
+            }
+        } else if subcommand == "empty" {
+            if !yes_mode_active {
+                let mut answer = String::new();
+
+                println!(
+                    "Type Y/y and hit enter to permanently delete the components in the trash."
+                );
+
+                io::stdin()
+                    .read_line(&mut answer)
+                    .expect("ERROR: Failed to read answer from user.");
+
+                // Emptying the trash cannot be undone
+                if &answer.trim().to_uppercase() != "Y" {
+                    println!("Aborting trash empty.");
+
+                    return;
+                }
+            }
+
+            match trash::empty(&get_cwd()) {
+                Ok(_) => println!("Trash empty finished."),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        } else {
+            eprintln!("trash subcommand not understood: {}", subcommand);
+            std::process::exit(1);
+        }
+    } else if command == "conflicts" {
+        let subcommand = &args[0];
+
+        if subcommand == "list" {
+            let conflicts = conflicts::list(&get_cwd());
+
+            if conflicts.is_empty() {
+                println!("There are no merge conflicts.");
+            }
+
+            let mut component = String::new();
+            for conflict in conflicts {
+                // Group the files by the component they belong to
+                if conflict.component != component {
+                    component = conflict.component.to_string();
+                    println!("{}:", component);
+                }
+
+                if conflict.can_merge {
+                    println!("    {} (can be merged automatically)", conflict.file);
+                } else {
+                    println!("    {} (choose --ours or --theirs)", conflict.file);
+                }
+            }
+        } else if subcommand == "resolve" {
+            if args.len() < 2 {
+                eprintln!("ERROR: Please supply the file to resolve the conflicts of.");
+                std::process::exit(3);
+            }
+
+            if ours && theirs {
+                eprintln!("ERROR: Only one of --ours and --theirs can be used.");
+                std::process::exit(3);
+            }
+
+            let side = if ours {
+                Some(conflicts::Side::Ours)
+            } else if theirs {
+                Some(conflicts::Side::Theirs)
+            } else {
+                None
+            };
+
+            match conflicts::resolve(&get_cwd(), &args[1], side) {
+                Ok(notes) => {
+                    for note in notes {
+                        println!("{}", note);
+                    }
+
+                    println!("Conflict resolve finished.");
+                }
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        } else {
+            eprintln!("conflicts subcommand not understood: {}", subcommand);
+            std::process::exit(1);
+        }
+    } else if command == "sync" {
+        // Syncing needs both the network and a repository to sync with
+        if offline {
+            eprintln!("ERROR: Components cannot be synced in offline mode.");
+            std::process::exit(5);
+        }
+        if !Path::new(".git").exists() {
+            eprintln!("ERROR: This component has not been uploaded yet. Run upload first.");
+            std::process::exit(2);
+        }
+
+        check_remote(&get_cwd(), &remote_name);
+
+        let changes = worktree::list_uncommitted(&get_cwd());
+        let unpushed = worktree::count_unpushed(&get_cwd()) != Some(0);
+
+        // Ask for the message up front so that nothing stops partway through the sequence
+        if (!changes.is_empty() || unpushed) && message.is_empty() {
+            println!("Message to attach to these project changes:");
+
+            io::stdin()
+                .read_line(&mut message)
+                .expect("ERROR: Failed to read upload message line from user");
+
+            message = message.trim().to_string();
+        }
+
+        // Local changes are set aside while the remote changes are downloaded underneath them
+        if !changes.is_empty() {
+            if let Err(e) = worktree::stash(&get_cwd()) {
+                eprintln!("ERROR: Could not set the local changes aside: {}", e);
+                std::process::exit(2);
+            }
+        }
+
+        let output = download_changes(&get_cwd(), &remote_name);
+
+        if verbose {
+            print_stdout(&output);
+        }
+
+        if report_conflicts("The downloaded changes") || transaction::has_failed(&output) {
+            print_stderr(&output);
+            if !changes.is_empty() {
+                println!("Your own changes were set aside with git stash and have not been put back. Run git stash pop once the download problem is fixed.");
+            }
+            eprintln!("ERROR: Component sync stopped, the remote changes could not be downloaded.");
+            std::process::exit(8);
+        }
+
+        if !changes.is_empty() {
+            if let Err(e) = worktree::unstash(&get_cwd()) {
+                if !report_conflicts("The downloaded changes") {
+                    eprintln!("ERROR: Could not put your changes back: {}", e);
+                }
+                println!("Your changes are still saved, and can be seen with git stash list.");
+                eprintln!("ERROR: Component sync stopped before uploading.");
+                std::process::exit(8);
+            }
+        }
+
+        let output = update_dependencies();
+
+        if verbose {
+            print_stdout(&output);
+        }
+
+        if transaction::has_failed(&output) {
+            print_stderr(&output);
+            eprintln!("ERROR: Component sync stopped, the dependencies could not be updated.");
+            std::process::exit(2);
+        }
+
+        // Commits left behind by an upload that could not push still need to go up
+        if changes.is_empty() && worktree::count_unpushed(&get_cwd()) == Some(0) {
+            println!("No local changes to upload.");
+        } else {
+            let output =
+                upload_changes(&get_cwd(), &remote_name, message, String::new(), None, None);
+
+            if verbose {
+                print_stdout(&output);
+            }
+
+            if transaction::has_failed(&output) {
+                print_stderr(&output);
+                eprintln!(
+                    "ERROR: Component sync stopped, the local changes could not be uploaded."
+                );
+                std::process::exit(2);
+            }
+        }
+
+        println!("Component sync finished.");
+    } else if command == "link" {
+        if args.len() < 2 {
+            eprintln!("ERROR: Please supply the name of the remote component and the path to a checkout of it.");
+            std::process::exit(3);
+        }
+
+        match link::link(&get_cwd(), &args[0], &args[1]) {
+            Ok(_) => println!("Component link finished."),
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        }
+    } else if command == "unlink" {
+        if args.is_empty() {
+            eprintln!("ERROR: Please supply the name of the remote component to unlink.");
+            std::process::exit(3);
+        }
+
+        if let Err(e) = link::unlink(&get_cwd(), &args[0]) {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(2);
+        }
+
+        // Install the component from its repository again in place of the checkout
+        let output = update_dependencies();
+
+        // Show extra output only when the user requests it
+        if verbose {
+            print_stdout(&output);
+        } else {
+            println!("Component unlink finished.");
+        }
+
+        // Show error information when it happens, whether the user has requested verbose output or not
+        if !output.stderr.is_empty() {
+            print_stderr(&output);
+        }
+    } else if command == "branch" {
+        let subcommand = &args[0];
+
+        // Every subcommand other than list works on a named branch
+        let needs_name = ["create", "switch", "merge", "delete"].contains(&subcommand.as_str());
+        if needs_name && args.len() < 2 {
+            eprintln!("ERROR: Please supply the name of the branch.");
+            std::process::exit(3);
+        }
+
+        if subcommand == "create" {
+            match branch::create(&get_cwd(), &args[1]) {
+                Ok(_) => println!("Branch create finished, now on branch {}.", args[1]),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        } else if subcommand == "list" {
+            let branches = match branch::list(&get_cwd()) {
+                Ok(branches) => branches,
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            };
+
+            let current = branch::current(&get_cwd()).unwrap_or_default();
+
+            for (name, remote_only) in branches {
+                let marker = if name == current { "*" } else { " " };
+
+                if remote_only {
+                    println!("{} {} (uploaded, not downloaded yet)", marker, name);
+                } else {
+                    println!("{} {}", marker, name);
+                }
+            }
+        } else if subcommand == "switch" {
+            if let Err(e) = branch::switch(&get_cwd(), &args[1]) {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+
+            // The other branch may use different remote components
+            let output = update_dependencies();
+
+            // Show extra output only when the user requests it
+            if verbose {
+                print_stdout(&output);
+            } else {
+                println!("Branch switch finished, now on branch {}.", args[1]);
+            }
+
+            // Show error information when it happens, whether the user has requested verbose output or not
+            if !output.stderr.is_empty() {
+                print_stderr(&output);
+            }
+        } else if subcommand == "merge" {
+            if let Err(e) = branch::merge(&get_cwd(), &args[1]) {
+                if !report_conflicts(&format!("The changes from branch {}", args[1])) {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+                std::process::exit(8);
+            }
+
+            // The merged branch may have added or removed remote components
+            let output = update_dependencies();
+
+            // Show extra output only when the user requests it
+            if verbose {
+                print_stdout(&output);
+            } else {
+                println!("Branch merge finished. Run upload to share the merged changes.");
+            }
+
+            // Show error information when it happens, whether the user has requested verbose output or not
+            if !output.stderr.is_empty() {
+                print_stderr(&output);
+            }
+        } else if subcommand == "delete" {
+            let mut result = branch::delete(&get_cwd(), &args[1], false);
+
+            // Branches that were never merged hold design changes that exist nowhere else
+            if let Err(ref e) = result {
+                if e.contains("not fully merged") {
+                    let mut answer = String::from("Y");
+
+                    if !yes_mode_active {
+                        answer.clear();
+
+                        println!(
+                            "The branch {} has changes that were never merged. Type Y/y and hit enter to delete it anyway.",
+                            args[1]
+                        );
+
+                        io::stdin()
+                            .read_line(&mut answer)
+                            .expect("ERROR: Failed to read answer from user.");
+                    }
+
+                    if answer.trim().to_uppercase() != "Y" {
+                        println!("Aborting branch delete.");
+                        return;
+                    }
+
+                    result = branch::delete(&get_cwd(), &args[1], true);
+                }
+            }
+
+            match result {
+                Ok(_) => println!("Branch delete finished."),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        } else {
+            eprintln!("branch subcommand not understood: {}", subcommand);
+            std::process::exit(1);
+        }
+    } else if command == "status" {
+        let current = match branch::current(&get_cwd()) {
+            Some(current) => current,
+            None => {
+                println!("This component has not been uploaded yet.");
+                return;
+            }
+        };
+
+        println!("On branch {}", current);
+
+        match worktree::count_unpushed(&get_cwd()) {
+            Some(0) => {}
+            Some(count) => println!("{} set(s) of changes are waiting to be uploaded.", count),
+            None => println!("This branch has not been uploaded yet."),
+        }
+
+        let changes = worktree::list_uncommitted(&get_cwd());
+
+        if changes.is_empty() {
+            println!("No changes since the last upload.");
+        } else {
+            println!("Changes that have not been uploaded:");
+            for change in changes {
+                println!("    {}", change);
+            }
+        }
+
+        if !worktree::list_conflicts(&get_cwd()).is_empty() {
+            println!("There are merge conflicts to resolve. Run conflicts list to see them.");
+        }
+    } else if command == "revert" {
+        let target = if to.is_empty() {
+            None
+        } else {
+            Some(to.as_str())
+        };
+
+        let upload = match revert::find_upload(&get_cwd(), target) {
+            Ok(upload) => upload,
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        };
+
+        // A local component that has been removed since the upload can still be brought back
+        let subcomponent = args.first().map(|name| {
+            component::find_local(&get_cwd(), name)
+                .unwrap_or_else(|| get_cwd().join("components").join(name))
+        });
+
+        let changed = match revert::revert(&get_cwd(), &upload, subcomponent.as_deref()) {
+            Ok(changed) => changed,
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        };
+
+        if changed.is_empty() {
+            println!("The component already matches that upload, there is nothing to revert.");
+            return;
+        }
+
+        for file in &changed {
+            println!("Reverted {}", file);
+        }
+
+        // Remote components are put back at the versions that were in use at the time
+        if changed
+            .iter()
+            .any(|file| file.ends_with("package.json") || file.ends_with("package-lock.json"))
+        {
+            let output = update_dependencies();
+
+            if verbose {
+                print_stdout(&output);
+            }
+
+            if !output.stderr.is_empty() {
+                print_stderr(&output);
+            }
+        }
+
+        println!("Component revert finished. Run upload to share the reverted design.");
+    } else if command == "checkout" {
+        if args.is_empty() {
+            eprintln!("ERROR: Please supply the tag or version of the release to check out.");
+            std::process::exit(3);
+        }
+
+        let release = &args[0];
+
+        // Releases can be checked out from the current component or straight from a repository URL
+        let (source, name, parent) = if !url.is_empty() {
+            let name = url
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or("component")
+                .trim_end_matches(".git")
+                .to_string();
+
+            (url.clone(), name, get_cwd())
+        } else {
+            if !Path::new(".git").exists() {
+                eprintln!("ERROR: This component has not been uploaded yet. Use -u to check out a release from a repository URL.");
+                std::process::exit(2);
+            }
+
+            let name = component::get_name(&get_cwd())
+                .unwrap_or_else(|| get_cwd().file_name().unwrap().to_string_lossy().to_string());
+
+            // Keep the release out of the component that it came from
+            let parent = get_cwd()
+                .parent()
+                .map(|parent| parent.to_path_buf())
+                .unwrap_or_else(get_cwd);
+
+            (get_cwd().to_string_lossy().to_string(), name, parent)
+        };
+
+        let target = if into.is_empty() {
+            parent.join(format!("{}-{}", name, release))
+        } else {
+            get_cwd().join(&into)
+        };
+
+        match checkout::checkout(&source, release, &target) {
+            Ok((commit, warnings)) => {
+                for warning in warnings {
+                    println!("WARNING: {}", warning);
+                }
+
+                println!(
+                    "Component checkout finished, release {} ({}) is in {}",
+                    release,
+                    &commit[..7.min(commit.len())],
+                    target.display()
+                );
+            }
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        }
+    } else if command == "remote" {
+        let subcommand = &args[0];
+
+        if !Path::new(".git").exists() {
+            eprintln!("ERROR: This component has not been uploaded yet. Run upload first.");
+            std::process::exit(2);
+        }
+
+        let result = if subcommand == "list" {
+            remote::list(&get_cwd()).map(|remotes| {
+                for (name, remote_url) in remotes {
+                    println!("{} {}", name, remote_url);
+                }
+            })
+        } else if subcommand == "add" || subcommand == "set-url" {
+            if args.len() < 3 {
+                eprintln!("ERROR: Please supply the name and URL of the remote.");
+                std::process::exit(3);
+            }
+
+            if subcommand == "add" {
+                remote::add(&get_cwd(), &args[1], &args[2])
+            } else {
+                remote::set_url(&get_cwd(), &args[1], &args[2])
+            }
+        } else if subcommand == "remove" {
+            if args.len() < 2 {
+                eprintln!("ERROR: Please supply the name of the remote.");
+                std::process::exit(3);
+            }
+
+            remote::remove(&get_cwd(), &args[1])
+        } else {
+            eprintln!("remote subcommand not understood: {}", subcommand);
+            std::process::exit(1);
+        };
+
+        match result {
+            Ok(_) if subcommand != "list" => println!("Remote {} finished.", subcommand),
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(2);
+            }
+        }
+    } else if command == "mirror" {
+        if args.is_empty() {
+            eprintln!("ERROR: Please supply the base URL of the host to mirror the project to.");
+            std::process::exit(3);
+        }
+        if offline {
+            eprintln!("ERROR: Projects cannot be mirrored in offline mode.");
+            std::process::exit(5);
+        }
+        if !Path::new(".git").exists() {
+            eprintln!("ERROR: This component has not been uploaded yet. Run upload first.");
+            std::process::exit(2);
+        }
+
+        let base_url = &args[0];
+
+        // The mirror is recorded as a remote of its own, so origin stays where it is
+        let mirror_remote = if remote_is_named {
+            remote_name.clone()
+        } else {
+            String::from("mirror")
+        };
+        if mirror_remote == remote::DEFAULT_REMOTE {
+            eprintln!(
+                "ERROR: The mirror needs a remote name other than {}.",
+                remote::DEFAULT_REMOTE
+            );
+            std::process::exit(3);
+        }
+
+        if !worktree::list_uncommitted(&get_cwd()).is_empty() {
+            println!("WARNING: Changes that have not been uploaded are not mirrored.");
+        }
+
+        // Remote components go first so that the project never points at a repository that is not there
+        let mirrored = match mirror::mirror_components(&get_cwd(), base_url, all, create_remote) {
+            Ok(mirrored) => mirrored,
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                if !create_remote {
+                    println!("Repositories that do not exist on the mirror yet can be created with --create-remote.");
+                }
+                std::process::exit(2);
+            }
+        };
+
+        for component in &mirrored {
+            println!(
+                "Component {} mirrored to {}",
+                component.name, component.new_url
+            );
+        }
+
+        match mirror::mirror_project(&get_cwd(), base_url, &mirror_remote, create_remote) {
+            Ok(project_url) => println!("Project mirrored to {}", project_url),
+            Err(e) => {
+                eprintln!("ERROR: Could not mirror the project: {}", e);
+                std::process::exit(2);
+            }
+        }
+
+        if rewrite_urls {
+            match mirror::rewrite_urls(&get_cwd(), &mirrored) {
+                Ok(changed) => {
+                    for file in changed {
+                        println!(
+                            "Updated {}",
+                            file.strip_prefix(get_cwd()).unwrap_or(&file).display()
+                        );
+                    }
+                }
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        }
+
+        println!("Project mirror finished.");
+
+        if rewrite_urls && !mirrored.is_empty() {
+            println!(
+                "Run download dependencies to install the mirrored components, and then upload to record the new URLs (with --remote {} to upload them to the mirror).",
+                mirror_remote
+            );
+        }
+    }
+
+    // The user has to supply a command, and it needs to be recognized
+    if command.is_empty() {
+        println!("Please supply a command. Run with -h to see the options.");
+        std::process::exit(1);
+    }
+}
+
+/*
+ * Prompts the user for an https username and a password, with a warning that doing so is a security concern.
+ */
+fn get_https_user_info() -> (String, String) {
+    let mut username = String::new();
+    let password: String;
+
+    println!(
+        "You need to enter a username and password for using an https URL. Please do that now."
+    );
+    println!("WARNING: Using https with sliderule-cli can lead to passwords being stored in plain text. It is better to use ssh.");
+    print!("User: ");
+    io::stdout().flush().ok().expect("Could not flush stdout");
+    io::stdin()
+        .read_line(&mut username)
+        .expect("ERROR: Failed to read username from user");
+
+    password = rpassword::prompt_password_stdout("Password: ").unwrap();
+
+    (username, password)
+}
+
+/*
+ * Prints all the lines of standard output to standard output.
+ */
+fn print_stdout(output: &SROutput) {
+    for line in &output.stdout {
+        if !line.is_empty() {
+            println!("{}", line);
+        }
+    }
+}
+
+/*
+ * Prints all the lines of standard output to standard output.
+ */
+fn print_stderr(output: &SROutput) {
+    for line in &output.stderr {
+        if !line.is_empty() {
+            println!("{}", line);
+        }
+    }
+}
+
+/*
+ * Warns the user about changes that have not been uploaded, and asks whether to set them aside,
+ * continue anyway or abort. Returns whether the changes were set aside, or None to abort.
+ */
+fn ask_about_uncommitted(yes_mode_active: bool) -> Option<bool> {
+    let changes = worktree::list_uncommitted(&get_cwd());
+
+    if changes.is_empty() {
+        return Some(false);
+    }
+
+    println!("This component has changes that have not been uploaded:");
+    for change in &changes {
+        println!("    {}", change);
+    }
+
+    // Setting the changes aside is the safe choice when nobody is there to answer
+    let answer = if yes_mode_active {
+        String::from("S")
+    } else {
+        let mut answer = String::new();
+
+        println!("Type S/s to set them aside with git stash and put them back afterwards, C/c to continue without setting them aside, or anything else to abort.");
+
+        io::stdin()
+            .read_line(&mut answer)
+            .expect("ERROR: Failed to read answer from user.");
+
+        answer.trim().to_uppercase()
+    };
+
+    if answer == "S" {
+        match worktree::stash(&get_cwd()) {
+            Ok(_) => Some(true),
+            Err(e) => {
+                eprintln!("ERROR: Could not set the changes aside: {}", e);
+                None
+            }
+        }
+    } else if answer == "C" {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/*
+ * Lists the files that git could not merge for each component, along with how to fix them.
+ * Returns whether there were any conflicts.
+ */
+fn report_conflicts(incoming: &str) -> bool {
+    let conflicts = worktree::list_conflicts(&get_cwd());
+
+    if conflicts.is_empty() {
+        return false;
+    }
+
+    println!(
+        "{} conflict with changes made here. These files need to be merged by hand:",
+        incoming
+    );
+
+    for (component, files) in &conflicts {
+        println!("{}:", component);
+        for file in files {
+            println!("    {}", file);
+        }
+    }
+
+    println!("Run conflicts resolve with the name of each file to merge it, then run upload to finish the merge.");
+
+    true
+}
+
+/*
+ * Shows the steps a command would take when it is run with --dry-run.
+ */
+fn print_plan(steps: Vec<String>) {
+    println!("Dry run, nothing has been changed. This command would:");
+
+    for step in steps {
+        println!("    {}", step);
+    }
+}
+
+/*
+ * Has npm update the remote components of the current component, keeping linked checkouts linked.
+ */
+fn update_dependencies() -> SROutput {
+    let links = link::list(&get_cwd());
+
+    let output = sliderule::update_dependencies(&get_cwd());
+
+    for error in link::relink(&get_cwd(), &links) {
+        println!("WARNING: {}", error);
+    }
+
+    output
+}
+
+/*
+ * Uploads the changes to a component. Sliderule only knows about the default branch and remote, so
+ * the changes on any other branch, or for any other remote, are uploaded here instead. The large
+ * files kept in Git LFS are uploaded first, so that nothing is pushed if they cannot be.
+ */
+fn upload_changes(
+    component_path: &Path,
+    remote_name: &str,
+    message: String,
+    url: String,
+    user: Option<String>,
+    pass: Option<String>,
+) -> SROutput {
+    // Components from before Sliderule used Git LFS start using it once they have large files to upload
+    match lfs::track_large_files(component_path) {
+        Ok(true) => println!(
+            "Large files in this component are now kept in Git LFS, as listed in .gitattributes."
+        ),
+        Ok(false) => {}
+        Err(e) => println!("WARNING: {}", e),
+    }
+
+    let uses_lfs = lfs::is_used(component_path);
+    if uses_lfs && !lfs::is_available() {
+        println!("WARNING: git-lfs is not installed, so the large files listed in .gitattributes are uploaded as ordinary files.");
+    }
+
+    let hooks_dir = if uses_lfs && lfs::is_available() {
+        match lfs::prepare_push(component_path) {
+            Ok(hooks_dir) => hooks_dir,
+            Err(e) => {
+                return SROutput {
+                    status: 0,
+                    wrapped_status: 1,
+                    stdout: Vec::new(),
+                    stderr: vec![format!(
+                        "Could not set up Git LFS to upload the large files: {}",
+                        e
+                    )],
+                };
+            }
+        }
+    } else {
+        None
+    };
+
+    let output = match branch::current(component_path) {
+        Some(ref current)
+            if *current != branch::get_default(component_path)
+                || remote_name != remote::DEFAULT_REMOTE =>
+        {
+            branch::upload(component_path, remote_name, current, &message)
+        }
+        _ => sliderule::upload_component(component_path, message, url, user, pass),
+    };
+
+    if let Err(e) = lfs::finish_push(component_path, hooks_dir) {
+        println!(
+            "WARNING: Git LFS could not be set up for later uploads made with git: {}",
+            e
+        );
+    }
+
+    output
+}
+
+/*
+ * Downloads the changes to a component from the branch that it is on, in the given remote, along
+ * with any large files kept in Git LFS that are still missing.
+ */
+fn download_changes(component_path: &Path, remote_name: &str) -> SROutput {
+    let mut output = match branch::current(component_path) {
+        Some(ref current)
+            if *current != branch::get_default(component_path)
+                || remote_name != remote::DEFAULT_REMOTE =>
+        {
+            branch::download(component_path, remote_name, current)
+        }
+        _ => sliderule::update_local_component(component_path),
+    };
+
+    if lfs::is_used(component_path) && lfs::is_available() && !transaction::has_failed(&output) {
+        if let Err(e) = lfs::pull(component_path, remote_name) {
+            output.stderr.push(format!(
+                "Could not download the large files with Git LFS: {}",
+                e
+            ));
+        }
+    }
+
+    output
+}
+
+/*
+ * Makes sure that a remote other than the default one exists before anything is done with it.
+ */
+fn check_remote(component_path: &Path, remote_name: &str) {
+    if remote_name == remote::DEFAULT_REMOTE {
+        return;
+    }
+
+    if let Err(e) = remote::get_url(component_path, remote_name) {
+        eprintln!("ERROR: {}", e);
+        std::process::exit(2);
+    }
+}
+
+/*
+ * Creates the repository that a component is about to be uploaded to, if it does not exist yet.
+ */
+fn create_remote_repository(url: &str) {
+    if url.is_empty() {
+        eprintln!("ERROR: There is no repository URL to create a repository for.");
+        std::process::exit(3);
+    }
+
+    match provision::create_repository(url) {
+        Ok(true) => println!("Repository {} created.", url),
+        Ok(false) => {}
+        Err(e) => {
+            eprintln!("ERROR: Could not create the repository: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
+/*
+ * Stores a copy of a remote component in the local cache, warning the user if that fails.
+ */
+fn cache_component(url: &str) {
+    if let Err(e) = cache::store(url) {
+        println!("WARNING: Could not cache {}: {}", url, e);
+    }
+}
+
+/*
+ * Stores copies of all the current component's remote dependencies in the local cache.
+ */
+fn cache_dependencies() {
+    for error in cache::store_dependencies(&get_cwd()) {
+        println!("WARNING: {}", error);
+    }
+}
+
+/*
+ * Starts a transaction, warning the user when a snapshot of the project could not be taken.
+ */
+fn start_transaction(transaction: Result<Transaction, String>) -> Option<Transaction> {
+    match transaction {
+        Ok(transaction) => Some(transaction),
+        Err(e) => {
+            println!(
+                "WARNING: {} The project cannot be restored if this command fails.",
+                e
+            );
+            None
+        }
+    }
+}
+
+/*
+ * Keeps the changes of a successful operation, or restores the project and exits if the operation failed.
+ */
+fn finish_transaction(transaction: Option<Transaction>, output: &SROutput, operation: &str) {
+    if transaction::has_failed(output) {
+        rollback_transaction(transaction, operation);
+        std::process::exit(2);
+    }
+
+    if let Some(transaction) = transaction {
+        transaction.commit();
+    }
+}
+
+/*
+ * Puts the project back the way it was before a failed operation.
+ */
+fn rollback_transaction(transaction: Option<Transaction>, operation: &str) {
+    if let Some(transaction) = transaction {
+        match transaction.rollback() {
+            Ok(_) => eprintln!(
+                "ERROR: Component {} failed, the project has been restored to how it was before.",
+                operation
+            ),
+            Err(e) => eprintln!(
+                "ERROR: Component {} failed, and the project could not be fully restored: {}",
+                operation, e
+            ),
+        }
+    }
+}
+
+/*
+ * Prompt the user to ask for licenses.
+ */
+fn ask_for_licenses(display_anyway: bool) -> (String, String) {
+    let licenses = sliderule::get_licenses(&get_cwd());
+    let default_src_license = licenses.0;
+    let default_docs_lic = licenses.1;
+
+    let mut source_license = String::new();
+    let mut doc_license = String::new();
+
+    // Ask the user for their license choice for the source of this component if they haven't specified it on the command line
+    if sliderule::get_level(&get_cwd()) == 0 || display_anyway {
         // Ask the user to choose a source license
         println!("Please choose a source license for this component.");
         println!("For a list of available licenses see https://spdx.org/licenses/");
@@ -551,3 +2478,35 @@ fn get_cwd() -> PathBuf {
 
     cwd
 }
+
+/*
+ * Gets a new, unique path in the system's temporary directory for a command to work in.
+ */
+fn get_temp_dir(purpose: &str) -> PathBuf {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("ERROR: System time is set before the UNIX epoch.");
+
+    env::temp_dir().join(format!(
+        "sliderule_{}_{}_{}{}",
+        purpose,
+        std::process::id(),
+        now.as_secs(),
+        now.subsec_nanos()
+    ))
+}
+
+/*
+ * Gets the directory that holds the settings of this user, such as their templates.
+ */
+fn get_config_dir() -> PathBuf {
+    let config_dir = if cfg!(windows) {
+        PathBuf::from(env::var("APPDATA").unwrap_or_else(|_| String::from(".")))
+    } else if let Ok(dir) = env::var("XDG_CONFIG_HOME") {
+        PathBuf::from(dir)
+    } else {
+        PathBuf::from(env::var("HOME").unwrap_or_else(|_| String::from("."))).join(".config")
+    };
+
+    config_dir.join("sliderule")
+}
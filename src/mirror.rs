@@ -0,0 +1,276 @@
+use component;
+use get_temp_dir;
+use provision;
+use remote;
+use std::collections::{BTreeSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tools;
+
+/*
+ * A remote component that has been pushed to the mirror.
+ */
+pub struct Mirrored {
+    pub name: String,
+    pub old_url: String,
+    pub new_url: String,
+}
+
+/*
+ * Builds the URL of a component's repository on the mirror. The base URL can hold a {{name}}
+ * placeholder, or the repository is put directly under it as <name>.git.
+ */
+pub fn get_mirror_url(base_url: &str, name: &str) -> String {
+    if base_url.contains("{{name}}") {
+        base_url.replace("{{name}}", name)
+    } else {
+        format!("{}/{}.git", base_url.trim_end_matches('/'), name)
+    }
+}
+
+/*
+ * Pushes the remote components of a project to the mirror, including the remote components that
+ * they use in turn. Only the components that live alongside the project's own repository are
+ * included, unless every remote component is asked for.
+ */
+pub fn mirror_components(
+    project_path: &Path,
+    base_url: &str,
+    all: bool,
+    create: bool,
+) -> Result<Vec<Mirrored>, String> {
+    let owner = remote::get_url(project_path, remote::DEFAULT_REMOTE)
+        .ok()
+        .and_then(|url| get_owner(&url));
+
+    let mut queue: VecDeque<(String, String)> = list_dependencies(project_path).into();
+    let mut seen: BTreeSet<String> = BTreeSet::new();
+    let mut mirrored: Vec<Mirrored> = Vec::new();
+
+    while let Some((name, location)) = queue.pop_front() {
+        if !is_git_location(&location) || !seen.insert(name.clone()) {
+            continue;
+        }
+
+        let old_url = component::get_git_url(&location);
+
+        // Components from other people are left where they are unless everything is mirrored
+        if !all && (owner.is_none() || get_owner(&old_url) != owner) {
+            continue;
+        }
+
+        let new_url = get_mirror_url(base_url, &name);
+        let clone_dir = get_temp_dir("mirror");
+
+        let result = push_mirror(&old_url, &new_url, &clone_dir, create);
+
+        // The components this one uses are found before its copy is cleaned up
+        if result.is_ok() {
+            if let Ok(contents) = tools::git(&clone_dir, &["show", "HEAD:package.json"]) {
+                queue.extend(component::parse_dependencies(&contents));
+            }
+        }
+        let _ = fs::remove_dir_all(&clone_dir);
+
+        result.map_err(|e| format!("Could not mirror {}: {}", name, e))?;
+
+        mirrored.push(Mirrored {
+            name,
+            old_url,
+            new_url,
+        });
+    }
+
+    Ok(mirrored)
+}
+
+/*
+ * Pushes every branch and tag of the project itself to the mirror, and records the mirror as a
+ * named remote so that later changes can be uploaded to it with --remote.
+ */
+pub fn mirror_project(
+    project_path: &Path,
+    base_url: &str,
+    remote_name: &str,
+    create: bool,
+) -> Result<String, String> {
+    let name = component::get_name(project_path).unwrap_or_else(|| {
+        project_path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string()
+    });
+    let url = get_mirror_url(base_url, &name);
+
+    if create {
+        provision::create_repository(&url)?;
+    }
+
+    if remote::get_url(project_path, remote_name).is_ok() {
+        remote::set_url(project_path, remote_name, &url)?;
+    } else {
+        remote::add(project_path, remote_name, &url)?;
+    }
+
+    tools::git(project_path, &["push", remote_name, "--all"])?;
+    tools::git(project_path, &["push", remote_name, "--tags"])?;
+
+    Ok(url)
+}
+
+/*
+ * Points the remote components of the project at their copies on the mirror, in the package.json
+ * and package-lock.json files of the project and its local components. Returns the files that
+ * were changed.
+ */
+pub fn rewrite_urls(project_path: &Path, mirrored: &[Mirrored]) -> Result<Vec<PathBuf>, String> {
+    let mut changed: Vec<PathBuf> = Vec::new();
+
+    for dir in list_component_dirs(project_path) {
+        for file in &["package.json", "package-lock.json"] {
+            let file_path = dir.join(file);
+
+            let contents = match fs::read_to_string(&file_path) {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+
+            let new_contents: Vec<String> = contents
+                .split('\n')
+                .map(|line| {
+                    mirrored
+                        .iter()
+                        .find_map(|component| {
+                            rewrite_line(line, &component.old_url, &get_npm_url(&component.new_url))
+                        })
+                        .unwrap_or_else(|| line.to_string())
+                })
+                .collect();
+            let new_contents = new_contents.join("\n");
+
+            if new_contents != contents {
+                fs::write(&file_path, new_contents)
+                    .map_err(|e| format!("Could not update {}: {}", file_path.display(), e))?;
+
+                changed.push(file_path);
+            }
+        }
+    }
+
+    Ok(changed)
+}
+
+/*
+ * Points a "key": "value" line of a package file at the mirror when its value is the old URL of a
+ * component, keeping the git+ prefix and the pinned commit that npm adds around the URL. Values
+ * that only start with the old URL are left alone.
+ */
+fn rewrite_line(line: &str, old_url: &str, new_url: &str) -> Option<String> {
+    let value = component::get_json_value(line)?;
+
+    if component::get_git_url(&value) != old_url {
+        return None;
+    }
+
+    let prefix = if value.starts_with("git+") {
+        "git+"
+    } else {
+        ""
+    };
+    let suffix = value.find('#').map(|index| &value[index..]).unwrap_or("");
+
+    let quoted = format!("\"{}\"", value);
+    let index = line.rfind(&quoted)?;
+
+    Some(format!(
+        "{}\"{}{}{}\"{}",
+        &line[..index],
+        prefix,
+        new_url,
+        suffix,
+        &line[index + quoted.len()..]
+    ))
+}
+
+/*
+ * Gets the form of a mirror URL that npm understands, since npm needs a scheme to tell local paths
+ * and scp-like ssh URLs apart.
+ */
+fn get_npm_url(url: &str) -> String {
+    if url.contains("://") {
+        url.to_string()
+    } else if provision::is_scp_url(url) {
+        // The path after the colon of an scp-like URL becomes the path of the ssh:// URL
+        format!("ssh://{}", url.replacen(':', "/", 1))
+    } else {
+        format!("file://{}", url)
+    }
+}
+
+/*
+ * Copies a repository with all of its branches and tags, and pushes it to the mirror.
+ */
+fn push_mirror(old_url: &str, new_url: &str, clone_dir: &Path, create: bool) -> Result<(), String> {
+    tools::git(
+        Path::new("."),
+        &["clone", "--mirror", old_url, &clone_dir.to_string_lossy()],
+    )?;
+
+    if create {
+        provision::create_repository(new_url)?;
+    }
+
+    tools::git(clone_dir, &["push", "--mirror", new_url]).map(|_| ())
+}
+
+/*
+ * Lists the remote components used by a project and by each of its local components.
+ */
+fn list_dependencies(project_path: &Path) -> Vec<(String, String)> {
+    list_component_dirs(project_path)
+        .iter()
+        .flat_map(|dir| component::get_dependencies(dir))
+        .collect()
+}
+
+/*
+ * Lists the directory of a project along with the directories of all of its local components.
+ */
+fn list_component_dirs(project_path: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![project_path.to_path_buf()];
+
+    if let Ok(entries) = fs::read_dir(project_path.join("components")) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            if entry.path().is_dir() {
+                dirs.extend(list_component_dirs(&entry.path()));
+            }
+        }
+    }
+
+    dirs
+}
+
+/*
+ * Tells whether the location of a dependency is a git repository rather than an npm version.
+ */
+fn is_git_location(location: &str) -> bool {
+    location.starts_with("git+") || location.contains("://") || location.ends_with(".git")
+}
+
+/*
+ * Gets the host and the user or group that a repository URL belongs to.
+ */
+fn get_owner(url: &str) -> Option<(String, String)> {
+    // Repositories on the local filesystem belong to the directory that holds them
+    if provision::is_local_url(url) {
+        return Path::new(url.trim_start_matches("file://"))
+            .parent()
+            .map(|parent| (String::new(), parent.to_string_lossy().to_string()));
+    }
+
+    let (host, path) = provision::split_url(url)?;
+
+    path.rfind('/')
+        .map(|index| (host, path[..index].to_string()))
+}
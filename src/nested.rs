@@ -0,0 +1,115 @@
+use component;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tools;
+use worktree;
+
+/*
+ * Finds the components inside of a component that have repositories of their own, such as local
+ * components that have been made into repositories and remote components that have been checked out
+ * for editing. The deepest components come first so that they can be uploaded before the
+ * components that use them.
+ */
+pub fn list_child_repos(component_path: &Path) -> Vec<PathBuf> {
+    let mut repos: Vec<PathBuf> = Vec::new();
+
+    for dir_name in &["components", "node_modules"] {
+        let entries = match fs::read_dir(component_path.join(dir_name)) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        let mut children: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        children.sort();
+
+        for child in children {
+            let has_repo = child.join(".git").exists();
+
+            // Installed components are only worth looking into when they are being edited
+            if *dir_name == "node_modules" && !has_repo {
+                continue;
+            }
+
+            repos.extend(list_child_repos(&child));
+
+            if has_repo {
+                repos.push(child);
+            }
+        }
+    }
+
+    repos
+}
+
+/*
+ * Tells whether a component has changes that have not been uploaded.
+ */
+pub fn has_changes(component_path: &Path) -> bool {
+    !worktree::list_uncommitted(component_path).is_empty()
+        || worktree::count_unpushed(component_path) != Some(0)
+}
+
+/*
+ * Points the component that installed a remote component at the commit that the remote component
+ * was just uploaded with. Local components do not need this, since the upload of the component
+ * holding them records their new commits. Returns whether any references were changed.
+ */
+pub fn update_references(child_path: &Path) -> Result<bool, String> {
+    let holder = child_path.parent().unwrap();
+
+    if holder
+        .file_name()
+        .map(|name| name != "node_modules")
+        .unwrap_or(true)
+    {
+        return Ok(false);
+    }
+
+    let parent_path = holder.parent().unwrap();
+    let name = child_path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+
+    let new_commit = tools::git(child_path, &["rev-parse", "HEAD"])?
+        .trim()
+        .to_string();
+
+    let old_commit = match component::get_locked_commit(parent_path, &name) {
+        Some(commit) => commit,
+        None => return Ok(false),
+    };
+
+    if old_commit == new_commit {
+        return Ok(false);
+    }
+
+    // A dependency pinned to a commit in package.json is moved along with the lock file
+    let mut changed = false;
+    for file in &["package-lock.json", "package.json"] {
+        let file_path = parent_path.join(file);
+
+        let contents = match fs::read_to_string(&file_path) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+
+        let old_ref = format!("#{}\"", old_commit);
+        if contents.contains(&old_ref) {
+            fs::write(
+                &file_path,
+                contents.replace(&old_ref, &format!("#{}\"", new_commit)),
+            )
+            .map_err(|e| format!("Could not update {}: {}", file_path.display(), e))?;
+
+            changed = true;
+        }
+    }
+
+    Ok(changed)
+}
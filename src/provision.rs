@@ -0,0 +1,457 @@
+use get_config_dir;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tools;
+
+/*
+ * The kinds of git servers that repositories can be created on through their HTTP API.
+ */
+#[derive(Clone, Copy, PartialEq)]
+enum Api {
+    Gitea,
+    GitLab,
+}
+
+/*
+ * A git server that repositories can be created on, as configured in the servers file.
+ */
+struct Server {
+    url: String,
+    api: Api,
+    token: String,
+}
+
+/*
+ * Gets the file that lists the git servers repositories can be created on, one per line as:
+ * <server url> <gitea | gitlab> <access token>
+ */
+pub fn get_servers_file() -> PathBuf {
+    get_config_dir().join("servers")
+}
+
+/*
+ * Creates the repository at a URL if it does not exist yet. Repositories on the local filesystem and
+ * on ssh hosts are created as bare repositories, and repositories on a configured Gitea or GitLab
+ * server are created through the server's API. Returns whether a repository was created.
+ */
+pub fn create_repository(url: &str) -> Result<bool, String> {
+    if is_local_url(url) {
+        return create_local(Path::new(url.trim_start_matches("file://")));
+    }
+
+    let (host, path) = match split_url(url) {
+        Some((ref host, ref path)) if !path.is_empty() => (host.clone(), path.clone()),
+        _ => return Err(format!("Could not work out the host and path of {}.", url)),
+    };
+
+    if let Some(server) = find_server(&host)? {
+        return create_on_server(&server, &path);
+    }
+
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return Err(format!(
+            "No server is configured for {}. Add it to {} with its API type and an access token.",
+            host,
+            get_servers_file().display()
+        ));
+    }
+
+    create_over_ssh(url, &path)
+}
+
+/*
+ * Creates a bare repository on the local filesystem, such as on a shared network drive.
+ */
+fn create_local(path: &Path) -> Result<bool, String> {
+    if path.join("HEAD").exists() {
+        return Ok(false);
+    }
+
+    if fs::read_dir(path)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false)
+    {
+        return Err(format!(
+            "{} already exists and is not a repository.",
+            path.display()
+        ));
+    }
+
+    fs::create_dir_all(path).map_err(|e| format!("Could not create {}: {}", path.display(), e))?;
+
+    tools::git(path, &["init", "--bare"]).map(|_| true)
+}
+
+/*
+ * Creates a bare repository on a host that can be logged in to with ssh.
+ */
+fn create_over_ssh(url: &str, path: &str) -> Result<bool, String> {
+    if can_reach(url) {
+        return Ok(false);
+    }
+
+    // ssh:// URLs can carry a port, and their paths start at the root of the host
+    let (destination, port, repo_path) = if let Some(rest) = url.strip_prefix("ssh://") {
+        let authority = rest.split('/').next().unwrap_or("");
+
+        match authority.rfind(':') {
+            Some(index) => (
+                authority[..index].to_string(),
+                Some(authority[index + 1..].to_string()),
+                format!("/{}", path),
+            ),
+            None => (authority.to_string(), None, format!("/{}", path)),
+        }
+    } else {
+        // scp-like URLs, such as user@host:path, have paths relative to the user's home directory
+        let index = url.find(':').unwrap_or(url.len());
+
+        (url[..index].to_string(), None, path.to_string())
+    };
+
+    let mut command = Command::new("ssh");
+    command.args(&["-o", "BatchMode=yes"]);
+    if let Some(ref port) = port {
+        command.args(&["-p", port]);
+    }
+
+    let output = command
+        .arg(&destination)
+        .arg(format!(
+            "git init --bare '{}'",
+            repo_path.replace('\'', "'\\''")
+        ))
+        .output()
+        .map_err(|e| format!("Could not run ssh: {}", e))?;
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        Err(format!(
+            "Could not create the repository on {}: {}",
+            destination,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/*
+ * Creates a repository through the API of a Gitea or GitLab server.
+ */
+fn create_on_server(server: &Server, path: &str) -> Result<bool, String> {
+    // Servers can be hosted under a path of their own, which is not part of the repository's path
+    let prefix = split_url(&server.url)
+        .map(|parts| format!("{}/", parts.1))
+        .unwrap_or_default();
+    let path = path
+        .trim_start_matches(prefix.as_str())
+        .trim_end_matches(".git");
+
+    // The repository goes in the user or group that the URL names
+    let (owner, name) = match path.rfind('/') {
+        Some(index) => (&path[..index], &path[index + 1..]),
+        None => {
+            return Err(format!(
+                "{} does not name the user or group to create the repository in.",
+                path
+            ))
+        }
+    };
+
+    let base = server.url.trim_end_matches('/');
+
+    match server.api {
+        Api::Gitea => {
+            let auth = format!("Authorization: token {}", server.token);
+
+            let (status, _) = request(
+                "GET",
+                &format!("{}/api/v1/repos/{}/{}", base, owner, name),
+                &auth,
+                None,
+            )?;
+            if status == 200 {
+                return Ok(false);
+            }
+
+            // Repositories of other owners have to be created in their organization
+            let (status, body) = request("GET", &format!("{}/api/v1/user", base), &auth, None)?;
+            check_status(status, &body, "find the user of the access token")?;
+
+            let endpoint = if find_json_value(&body, "login").as_deref() == Some(owner) {
+                format!("{}/api/v1/user/repos", base)
+            } else {
+                format!("{}/api/v1/orgs/{}/repos", base, owner)
+            };
+
+            let (status, body) = request(
+                "POST",
+                &endpoint,
+                &auth,
+                Some(&format!("{{\"name\": \"{}\"}}", escape_json(name))),
+            )?;
+            check_status(status, &body, "create the repository")?;
+        }
+        Api::GitLab => {
+            let auth = format!("PRIVATE-TOKEN: {}", server.token);
+
+            let (status, _) = request(
+                "GET",
+                &format!("{}/api/v4/projects/{}", base, encode(path)),
+                &auth,
+                None,
+            )?;
+            if status == 200 {
+                return Ok(false);
+            }
+
+            let (status, body) = request(
+                "GET",
+                &format!("{}/api/v4/namespaces/{}", base, encode(owner)),
+                &auth,
+                None,
+            )?;
+            check_status(status, &body, &format!("find the group {}", owner))?;
+
+            let namespace = find_json_value(&body, "id")
+                .ok_or_else(|| format!("The server did not give the ID of the group {}.", owner))?;
+
+            let (status, body) = request(
+                "POST",
+                &format!("{}/api/v4/projects", base),
+                &auth,
+                Some(&format!(
+                    "{{\"name\": \"{}\", \"path\": \"{}\", \"namespace_id\": {}}}",
+                    escape_json(name),
+                    escape_json(name),
+                    namespace
+                )),
+            )?;
+            check_status(status, &body, "create the repository")?;
+        }
+    }
+
+    Ok(true)
+}
+
+/*
+ * Finds the configured server for a host, if there is one.
+ */
+fn find_server(host: &str) -> Result<Option<Server>, String> {
+    let contents = match fs::read_to_string(get_servers_file()) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(None),
+    };
+
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 3 {
+            return Err(format!(
+                "Could not read the server line \"{}\" in {}. It should be: <server url> <gitea | gitlab> <access token>",
+                line,
+                get_servers_file().display()
+            ));
+        }
+
+        let api = match fields[1].to_lowercase().as_str() {
+            "gitea" => Api::Gitea,
+            "gitlab" => Api::GitLab,
+            other => {
+                return Err(format!(
+                    "{} is not a supported server type. Use gitea or gitlab.",
+                    other
+                ))
+            }
+        };
+
+        if split_url(fields[0]).map(|parts| parts.0).as_deref() == Some(host) {
+            return Ok(Some(Server {
+                url: fields[0].to_string(),
+                api,
+                token: fields[2].to_string(),
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/*
+ * Tells whether a repository URL is a path on the local filesystem, including file:// URLs and
+ * Windows paths that start with a drive letter.
+ */
+pub fn is_local_url(url: &str) -> bool {
+    url.starts_with("file://") || (!url.contains("://") && !is_scp_url(url))
+}
+
+/*
+ * Tells whether a repository URL has the scp-like [user@]host:path form. Like git, a colon only
+ * counts when no slash comes before it, and a single letter before it is a Windows drive.
+ */
+pub fn is_scp_url(url: &str) -> bool {
+    if url.contains("://") {
+        return false;
+    }
+
+    match url.find(':') {
+        Some(colon) => {
+            let before = &url[..colon];
+
+            !before.contains('/')
+                && !before.contains('\\')
+                && !(colon == 1 && before.chars().all(|c| c.is_ascii_alphabetic()))
+        }
+        None => false,
+    }
+}
+
+/*
+ * Splits a URL into its host and the path on that host. The host leaves out any user and port.
+ */
+pub fn split_url(url: &str) -> Option<(String, String)> {
+    let (authority, path) = match url.find("://") {
+        Some(index) => {
+            let rest = &url[index + 3..];
+            let slash = rest.find('/').unwrap_or(rest.len());
+
+            (&rest[..slash], rest[slash..].trim_start_matches('/'))
+        }
+        None => {
+            let colon = url.find(':')?;
+
+            (&url[..colon], &url[colon + 1..])
+        }
+    };
+
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+
+    if host.is_empty() {
+        return None;
+    }
+
+    Some((host.to_string(), path.trim_matches('/').to_string()))
+}
+
+/*
+ * Tells whether a repository already exists, without waiting for a password or host key prompt.
+ */
+fn can_reach(url: &str) -> bool {
+    let mut command = Command::new("git");
+    command
+        .args(&["ls-remote", "--heads", url])
+        .env("GIT_TERMINAL_PROMPT", "0");
+    if env::var("GIT_SSH_COMMAND").is_err() {
+        command.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+    }
+
+    command
+        .output()
+        .map(|out| out.status.success())
+        .unwrap_or(false)
+}
+
+/*
+ * Makes a request to a server's API, giving back the HTTP status and the body of the response.
+ */
+fn request(
+    method: &str,
+    url: &str,
+    auth: &str,
+    body: Option<&str>,
+) -> Result<(u32, String), String> {
+    let mut args = vec!["-s", "-S", "-X", method, "-w", "\n%{http_code}"];
+    if let Some(body) = body {
+        args.extend(&["-H", "Content-Type: application/json", "-d", body]);
+    }
+    args.push(url);
+
+    // The access token goes in the config on standard input rather than on the command line
+    let config = format!(
+        "header = \"{}\"\n",
+        auth.replace('\\', "\\\\").replace('"', "\\\"")
+    );
+
+    let output = tools::curl(&args, &config)?;
+
+    // The status code is written on its own line after the body
+    let index = output.rfind('\n').unwrap_or(0);
+    let status = output[index..]
+        .trim()
+        .parse()
+        .map_err(|_| format!("Could not understand the response from {}.", url))?;
+
+    Ok((status, output[..index].to_string()))
+}
+
+/*
+ * Turns an unsuccessful response into an error.
+ */
+fn check_status(status: u32, body: &str, action: &str) -> Result<(), String> {
+    if (200..300).contains(&status) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The server could not {} (HTTP {}): {}",
+            action,
+            status,
+            body.trim()
+        ))
+    }
+}
+
+/*
+ * Finds the first value of a key in a JSON response, as a string or a number.
+ */
+fn find_json_value(body: &str, key: &str) -> Option<String> {
+    let pattern = format!("\"{}\"", key);
+    let rest = body[body.find(&pattern)? + pattern.len()..].trim_start();
+    let rest = rest.strip_prefix(':')?.trim_start();
+
+    if let Some(rest) = rest.strip_prefix('"') {
+        return rest.find('"').map(|end| rest[..end].to_string());
+    }
+
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+
+    if end == 0 {
+        None
+    } else {
+        Some(rest[..end].to_string())
+    }
+}
+
+/*
+ * Encodes a path so that it can be used as a single part of an API URL.
+ */
+fn encode(path: &str) -> String {
+    path.replace('%', "%25").replace('/', "%2F")
+}
+
+/*
+ * Escapes text so that it can be put inside of a JSON string.
+ */
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::new();
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
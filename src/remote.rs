@@ -0,0 +1,174 @@
+use std::path::Path;
+use tools;
+
+/*
+ * The remote that Sliderule uploads to and downloads from when no other remote is named.
+ */
+pub const DEFAULT_REMOTE: &str = "origin";
+
+/*
+ * Lists the remotes of a component along with their URLs, with any passwords hidden.
+ */
+pub fn list(component_path: &Path) -> Result<Vec<(String, String)>, String> {
+    let names = tools::git(component_path, &["remote"])?;
+
+    let mut remotes: Vec<(String, String)> = Vec::new();
+
+    for name in names
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+    {
+        let url = get_url(component_path, name)?;
+
+        remotes.push((name.to_string(), hide_password(&url)));
+    }
+
+    Ok(remotes)
+}
+
+/*
+ * Gets the URL of a remote.
+ */
+pub fn get_url(component_path: &Path, name: &str) -> Result<String, String> {
+    tools::git(component_path, &["remote", "get-url", name])
+        .map(|url| url.trim().to_string())
+        .map_err(|_| {
+            format!(
+                "This component has no remote named {}. Add it with remote add.",
+                name
+            )
+        })
+}
+
+/*
+ * Adds a remote that the component can be uploaded to and downloaded from by name.
+ */
+pub fn add(component_path: &Path, name: &str, url: &str) -> Result<(), String> {
+    tools::git(component_path, &["remote", "add", name, url]).map(|_| ())
+}
+
+/*
+ * Removes a remote. The default remote is what Sliderule uploads to, so it can only be changed.
+ */
+pub fn remove(component_path: &Path, name: &str) -> Result<(), String> {
+    if name == DEFAULT_REMOTE {
+        return Err(format!(
+            "{} is the remote that this component is uploaded to by default. Use remote set-url to change it instead.",
+            name
+        ));
+    }
+
+    get_url(component_path, name)?;
+
+    tools::git(component_path, &["remote", "remove", name]).map(|_| ())
+}
+
+/*
+ * Changes the URL of a remote.
+ */
+pub fn set_url(component_path: &Path, name: &str, url: &str) -> Result<(), String> {
+    get_url(component_path, name)?;
+
+    tools::git(component_path, &["remote", "set-url", name, url]).map(|_| ())
+}
+
+/*
+ * Sets the username and password used for a remote other than the default one, which Sliderule
+ * handles itself. As with the default remote, they are kept in the URL of the remote.
+ */
+pub fn login(
+    component_path: &Path,
+    name: &str,
+    url: &str,
+    user: Option<String>,
+    pass: Option<String>,
+) -> Result<(), String> {
+    // Only http URLs carry passwords, since ssh logs in with keys
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return set_url(component_path, name, url);
+    }
+
+    let url = remove_userinfo(url);
+    let index = url.find("://").unwrap() + 3;
+
+    let url = match (user, pass) {
+        (Some(user), Some(pass)) => format!(
+            "{}{}:{}@{}",
+            &url[..index],
+            encode(&user),
+            encode(&pass),
+            &url[index..]
+        ),
+        _ => url,
+    };
+
+    set_url(component_path, name, &url)
+}
+
+/*
+ * Hides the password in a URL so that it can be shown.
+ */
+fn hide_password(url: &str) -> String {
+    let start = match url.find("://") {
+        Some(index) => index + 3,
+        None => return url.to_string(),
+    };
+
+    let authority_end = url[start..]
+        .find('/')
+        .map(|i| start + i)
+        .unwrap_or(url.len());
+
+    match url[start..authority_end].rfind('@') {
+        Some(at) => {
+            let userinfo = &url[start..start + at];
+
+            match userinfo.find(':') {
+                Some(colon) => format!(
+                    "{}{}:****{}",
+                    &url[..start],
+                    &userinfo[..colon],
+                    &url[start + at..]
+                ),
+                None => url.to_string(),
+            }
+        }
+        None => url.to_string(),
+    }
+}
+
+/*
+ * Takes any username and password out of a URL.
+ */
+fn remove_userinfo(url: &str) -> String {
+    let start = match url.find("://") {
+        Some(index) => index + 3,
+        None => return url.to_string(),
+    };
+
+    let authority_end = url[start..]
+        .find('/')
+        .map(|i| start + i)
+        .unwrap_or(url.len());
+
+    match url[start..authority_end].rfind('@') {
+        Some(at) => format!("{}{}", &url[..start], &url[start + at + 1..]),
+        None => url.to_string(),
+    }
+}
+
+/*
+ * Encodes a username or password so that it can be put into a URL.
+ */
+fn encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                (byte as char).to_string()
+            } else {
+                format!("%{:02X}", byte)
+            }
+        })
+        .collect()
+}
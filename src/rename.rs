@@ -0,0 +1,174 @@
+use component;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/*
+ * Gives a local component a new name, updating its directory, its own files and the bill of
+ * materials of the component that holds it.
+ */
+pub fn rename_component(project_path: &Path, old_name: &str, new_name: &str) -> Result<(), String> {
+    check_name(new_name)?;
+
+    let old_path = find(project_path, old_name)?;
+    let new_path = old_path.parent().unwrap().join(new_name);
+
+    if new_path.exists() {
+        return Err(format!(
+            "A component named {} already exists there.",
+            new_name
+        ));
+    }
+
+    fs::rename(&old_path, &new_path)
+        .map_err(|e| format!("Could not rename the {} directory: {}", old_name, e))?;
+
+    component::set_name(&new_path, old_name, new_name)
+        .map_err(|e| format!("Could not update the files of {}: {}", new_name, e))?;
+
+    // The holding component is two levels up, above the components directory
+    let parent_path = new_path.parent().unwrap().parent().unwrap();
+    let bom_file = parent_path.join("bom_data.yaml");
+
+    if let Ok(contents) = fs::read_to_string(&bom_file) {
+        fs::write(
+            &bom_file,
+            component::replace_name(&contents, old_name, new_name),
+        )
+        .map_err(|e| format!("Could not update {}: {}", bom_file.display(), e))?;
+    }
+
+    Ok(())
+}
+
+/*
+ * Moves a local component so that it is held by a different component of the project, along with
+ * the parts that mention it in the old holding component's bill of materials.
+ */
+pub fn move_component(project_path: &Path, name: &str, new_parent: &str) -> Result<(), String> {
+    let old_path = find(project_path, name)?;
+    let old_parent_path = old_path.parent().unwrap().parent().unwrap().to_path_buf();
+
+    // The project itself can be named as the new parent to move a component to the top level
+    let new_parent_path =
+        if new_parent == "." || component::get_name(project_path) == Some(new_parent.to_string()) {
+            project_path.to_path_buf()
+        } else {
+            find(project_path, new_parent)?
+        };
+
+    if new_parent_path.starts_with(&old_path) {
+        return Err(format!("{} cannot be moved inside of itself.", name));
+    }
+
+    let new_path = new_parent_path.join("components").join(name);
+    if new_path.exists() {
+        return Err(format!(
+            "{} already holds a component named {}.",
+            new_parent, name
+        ));
+    }
+
+    fs::create_dir_all(new_parent_path.join("components"))
+        .map_err(|e| format!("Could not create the components directory: {}", e))?;
+    fs::rename(&old_path, &new_path)
+        .map_err(|e| format!("Could not move the {} directory: {}", name, e))?;
+
+    move_bom_entries(
+        &old_parent_path.join("bom_data.yaml"),
+        &new_parent_path.join("bom_data.yaml"),
+        name,
+    )
+}
+
+/*
+ * Moves the parts that mention a component from one bill of materials to the same section of
+ * another, such as parts, adding the section if it is not there yet.
+ */
+fn move_bom_entries(old_bom: &Path, new_bom: &Path, name: &str) -> Result<(), String> {
+    let contents = match fs::read_to_string(old_bom) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(()),
+    };
+
+    let (header, entries) = component::parse_bom(&contents);
+
+    // Only parts listed under a section are moved, since top level entries describe the holder itself
+    let (moved, kept): (Vec<_>, Vec<_>) = entries.into_iter().partition(|(key, text)| {
+        key.contains('/') && component::replace_name(text, name, "") != *text
+    });
+
+    if moved.is_empty() {
+        return Ok(());
+    }
+
+    let (new_header, mut new_entries) =
+        component::parse_bom(&fs::read_to_string(new_bom).unwrap_or_default());
+
+    for (key, text) in moved {
+        let section = key.split('/').next().unwrap().to_string();
+        let prefix = format!("{}/", section);
+
+        let position = match new_entries
+            .iter()
+            .rposition(|entry| entry.0 == section || entry.0.starts_with(&prefix))
+        {
+            Some(position) => position + 1,
+            None => {
+                new_entries.push((section.to_string(), String::new()));
+                new_entries.len()
+            }
+        };
+
+        // An empty section, such as parts: {}, has to be opened up to hold the part
+        let heading = new_entries
+            .iter_mut()
+            .find(|entry| entry.0 == section)
+            .unwrap();
+        if !heading.1.trim().ends_with(':') {
+            heading.1 = format!("{}:\n", section);
+        }
+
+        new_entries.insert(position, (key, text));
+    }
+
+    fs::write(new_bom, join_bom(&new_header, &new_entries))
+        .map_err(|e| format!("Could not update {}: {}", new_bom.display(), e))?;
+    fs::write(old_bom, join_bom(&header, &kept))
+        .map_err(|e| format!("Could not update {}: {}", old_bom.display(), e))
+}
+
+/*
+ * Puts a bill of materials back together from the parts that parse_bom splits it into.
+ */
+fn join_bom(header: &str, entries: &[(String, String)]) -> String {
+    let mut contents = header.to_string();
+
+    for (_, text) in entries {
+        contents.push_str(text);
+    }
+
+    contents
+}
+
+/*
+ * Finds a local component by name, with an error the user can act on if it does not exist.
+ */
+fn find(project_path: &Path, name: &str) -> Result<PathBuf, String> {
+    component::find_local(project_path, name).ok_or_else(|| {
+        format!(
+            "There is no local component named {}. Only local components can be renamed or moved.",
+            name
+        )
+    })
+}
+
+/*
+ * Makes sure a new component name can be used as a directory name.
+ */
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains('/') || name.contains('\\') || name.starts_with('.') {
+        Err(format!("{} is not a valid component name.", name))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use std::path::Path;
+use tools;
+use worktree;
+
+/*
+ * An earlier upload of a component, as listed by changes history.
+ */
+pub struct Upload {
+    pub id: String,
+    pub date: String,
+    pub message: String,
+    pub tags: Vec<String>,
+}
+
+/*
+ * Lists the uploads of a component, newest first.
+ */
+pub fn history(component_path: &Path) -> Result<Vec<Upload>, String> {
+    let log = tools::git(
+        component_path,
+        &["log", "--date=short", "--format=%h%x09%ad%x09%s%x09%D"],
+    )?;
+
+    let mut uploads: Vec<Upload> = Vec::new();
+
+    for line in log.lines() {
+        let fields: Vec<&str> = line.splitn(4, '\t').collect();
+
+        if fields.len() < 3 {
+            continue;
+        }
+
+        // Ref names come as a comma separated list, such as HEAD -> master, tag: v1.0
+        let tags = fields
+            .get(3)
+            .unwrap_or(&"")
+            .split(", ")
+            .filter_map(|name| name.strip_prefix("tag: "))
+            .map(|tag| tag.to_string())
+            .collect();
+
+        uploads.push(Upload {
+            id: fields[0].to_string(),
+            date: fields[1].to_string(),
+            message: fields[2].to_string(),
+            tags,
+        });
+    }
+
+    Ok(uploads)
+}
+
+/*
+ * Finds the upload to revert to from an upload ID, a tag or a date. A date refers to the last upload
+ * made on or before that day, and no target at all refers to the upload before the latest one.
+ */
+pub fn find_upload(component_path: &Path, to: Option<&str>) -> Result<String, String> {
+    let to = match to {
+        Some(to) => to,
+        None => {
+            return tools::git(
+                component_path,
+                &["rev-parse", "--verify", "HEAD~1^{commit}"],
+            )
+            .map(|id| id.trim().to_string())
+            .map_err(|_| String::from("There is no earlier upload to revert to."));
+        }
+    };
+
+    if is_date(to) {
+        let before = format!("--before={} 23:59:59", to);
+        let id = tools::git(component_path, &["rev-list", "-1", &before, "HEAD"])?;
+
+        if id.trim().is_empty() {
+            return Err(format!("There were no uploads on or before {}.", to));
+        }
+
+        return Ok(id.trim().to_string());
+    }
+
+    tools::git(
+        component_path,
+        &["rev-parse", "--verify", &format!("{}^{{commit}}", to)],
+    )
+    .map(|id| id.trim().to_string())
+    .map_err(|_| format!("Could not find an upload ID, tag or date matching {}.", to))
+}
+
+/*
+ * Puts the files of a component, or of only one of its local components, back the way they were at
+ * an earlier upload. The history is kept, so the revert is uploaded as a new change. Returns the
+ * files that were changed.
+ */
+pub fn revert(
+    project_path: &Path,
+    upload: &str,
+    subcomponent: Option<&Path>,
+) -> Result<Vec<String>, String> {
+    // Mixing the revert in with changes that were never uploaded would make it hard to undo
+    if !worktree::list_uncommitted(project_path).is_empty() {
+        return Err(String::from(
+            "This component has changes that have not been uploaded. Upload them before reverting.",
+        ));
+    }
+
+    let pathspec = match subcomponent {
+        Some(path) => path
+            .strip_prefix(project_path)
+            .map_err(|_| format!("{} is not inside this component.", path.display()))?
+            .to_string_lossy()
+            .replace('\\', "/"),
+        None => String::from("."),
+    };
+
+    // Restoring also deletes the files that were added after the upload
+    tools::git(
+        project_path,
+        &[
+            "restore",
+            &format!("--source={}", upload),
+            "--staged",
+            "--worktree",
+            "--",
+            &pathspec,
+        ],
+    )?;
+
+    Ok(worktree::list_uncommitted(project_path))
+}
+
+/*
+ * Tells whether a revert target looks like a YYYY-MM-DD date.
+ */
+fn is_date(to: &str) -> bool {
+    let parts: Vec<&str> = to.split('-').collect();
+
+    parts.len() == 3
+        && parts[0].len() == 4
+        && parts[1].len() == 2
+        && parts[2].len() == 2
+        && parts
+            .iter()
+            .all(|part| part.chars().all(|c| c.is_ascii_digit()))
+}
@@ -0,0 +1,261 @@
+use component;
+use get_cwd;
+use get_temp_dir;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/*
+ * Tells whether the source given to add refers to the local filesystem instead of a remote repository.
+ */
+pub fn is_local_source(source: &str) -> bool {
+    // Archives at http:// and other URLs are left for npm to download
+    source.starts_with("file://")
+        || (is_archive(source) && !source.contains("://"))
+        || Path::new(source).is_dir()
+}
+
+/*
+ * A local component that is ready for npm to install, along with the temporary files that were
+ * made to get it ready.
+ */
+pub struct Source {
+    pub path: PathBuf,
+    temp_paths: Vec<PathBuf>,
+}
+
+impl Source {
+    /*
+     * Deletes the unpacked and repacked copies of an archive once npm has installed it.
+     */
+    pub fn clean_up(self) {
+        for path in self.temp_paths {
+            if path.is_dir() {
+                let _ = fs::remove_dir_all(&path);
+            } else {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+/*
+ * Checks that a local directory, file:// URL or archive holds a valid Sliderule component, and
+ * gives back the path that npm should install the component from.
+ */
+pub fn prepare(source: &str) -> Result<Source, Vec<String>> {
+    let mut path = PathBuf::from(strip_file_scheme(source));
+
+    // npm needs to be able to find the source from inside the project directory
+    if path.is_relative() {
+        path = get_cwd().join(path);
+    }
+
+    if !path.exists() {
+        return Err(vec![format!("{} does not exist.", path.display())]);
+    }
+
+    // A directory can be checked in place
+    if path.is_dir() {
+        let problems = component::find_problems(&path);
+
+        if problems.is_empty() {
+            return Ok(Source {
+                path,
+                temp_paths: Vec::new(),
+            });
+        } else {
+            return Err(problems);
+        }
+    }
+
+    if !is_archive(source) {
+        return Err(vec![String::from(
+            "Local components must be a directory, a .tar.gz/.tgz archive or a .zip archive.",
+        )]);
+    }
+
+    // Archives have to be unpacked before their contents can be checked
+    let staging_dir = get_temp_dir("archive");
+    fs::create_dir_all(&staging_dir).map_err(|e| {
+        vec![format!(
+            "Could not create staging directory {}: {}",
+            staging_dir.display(),
+            e
+        )]
+    })?;
+
+    let result = prepare_archive(source, &path, &staging_dir);
+
+    // The unpacked copy is only needed for checking, unless it had to be repacked for npm
+    match result {
+        Ok(ref tarball) if *tarball != path => Ok(Source {
+            path: tarball.to_path_buf(),
+            temp_paths: vec![staging_dir, tarball.to_path_buf()],
+        }),
+        Ok(_) => {
+            let _ = fs::remove_dir_all(&staging_dir);
+
+            Ok(Source {
+                path,
+                temp_paths: Vec::new(),
+            })
+        }
+        Err(problems) => {
+            let _ = fs::remove_dir_all(&staging_dir);
+
+            Err(problems)
+        }
+    }
+}
+
+/*
+ * Unpacks an archive into the staging directory and checks the component inside of it. Returns
+ * the path that npm should install the component from.
+ */
+fn prepare_archive(source: &str, path: &Path, staging_dir: &Path) -> Result<PathBuf, Vec<String>> {
+    extract(path, staging_dir).map_err(|e| vec![e])?;
+
+    let root = find_component_root(staging_dir);
+
+    let problems = component::find_problems(&root);
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    // npm can install tarballs directly, but zip files have to be repacked first
+    if source.to_lowercase().ends_with(".zip") {
+        // The tarball sits beside the staging directory so that it does not end up packing itself
+        let tarball = PathBuf::from(format!(
+            "{}_{}.tgz",
+            staging_dir.display(),
+            get_archive_stem(path)
+        ));
+
+        pack(&root, &tarball).map_err(|e| vec![e])?;
+
+        Ok(tarball)
+    } else {
+        Ok(path.to_path_buf())
+    }
+}
+
+/*
+ * Tells whether a source is an archive format that can be added as a component.
+ */
+fn is_archive(source: &str) -> bool {
+    let source = source.to_lowercase();
+
+    source.ends_with(".tar.gz") || source.ends_with(".tgz") || source.ends_with(".zip")
+}
+
+/*
+ * Turns a file:// URL into a plain filesystem path.
+ */
+fn strip_file_scheme(source: &str) -> String {
+    let mut path = source.trim_start_matches("file://").to_string();
+
+    // file:///C:/... URLs leave a leading slash in front of the Windows drive letter
+    if cfg!(windows) && path.starts_with('/') && path.chars().nth(2) == Some(':') {
+        path.remove(0);
+    }
+
+    path
+}
+
+/*
+ * Gets the name of an archive without any of its extensions.
+ */
+fn get_archive_stem(archive: &Path) -> String {
+    let file_name = archive.file_name().unwrap().to_string_lossy().to_string();
+
+    file_name
+        .trim_end_matches(".tar.gz")
+        .trim_end_matches(".tgz")
+        .trim_end_matches(".zip")
+        .to_string()
+}
+
+/*
+ * Archives often wrap the component in a single top level directory, so look inside it when that happens.
+ */
+fn find_component_root(staging_dir: &Path) -> PathBuf {
+    if staging_dir.join("package.json").exists() {
+        return staging_dir.to_path_buf();
+    }
+
+    let entries: Vec<PathBuf> = match fs::read_dir(staging_dir) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+        Err(_) => return staging_dir.to_path_buf(),
+    };
+
+    if entries.len() == 1 && entries[0].is_dir() {
+        entries[0].clone()
+    } else {
+        staging_dir.to_path_buf()
+    }
+}
+
+/*
+ * Unpacks a tarball or zip file into the destination directory.
+ */
+fn extract(archive: &Path, dest: &Path) -> Result<(), String> {
+    let is_zip = archive.to_string_lossy().to_lowercase().ends_with(".zip");
+
+    // The tar that ships with Windows can handle zip files, but GNU tar cannot
+    let output = if is_zip && !cfg!(windows) {
+        Command::new("unzip")
+            .arg("-q")
+            .arg(archive)
+            .arg("-d")
+            .arg(dest)
+            .output()
+    } else {
+        Command::new("tar")
+            .arg("-xf")
+            .arg(archive)
+            .arg("-C")
+            .arg(dest)
+            .output()
+    };
+
+    match output {
+        Ok(ref out) if out.status.success() => Ok(()),
+        Ok(out) => Err(format!(
+            "Could not extract {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        )),
+        Err(e) => Err(format!("Could not extract {}: {}", archive.display(), e)),
+    }
+}
+
+/*
+ * Packs a component directory into a tarball that npm can install.
+ */
+fn pack(component_dir: &Path, tarball: &Path) -> Result<(), String> {
+    let parent = component_dir.parent().unwrap();
+    let dir_name = component_dir.file_name().unwrap();
+
+    let output = Command::new("tar")
+        .arg("-czf")
+        .arg(tarball)
+        .arg("-C")
+        .arg(parent)
+        .arg(dir_name)
+        .output();
+
+    match output {
+        Ok(ref out) if out.status.success() => Ok(()),
+        Ok(out) => Err(format!(
+            "Could not repack {}: {}",
+            component_dir.display(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        )),
+        Err(e) => Err(format!(
+            "Could not repack {}: {}",
+            component_dir.display(),
+            e
+        )),
+    }
+}
@@ -0,0 +1,273 @@
+use component;
+use get_config_dir;
+use get_cwd;
+use get_temp_dir;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tools;
+
+/*
+ * Gets the directory that holds the templates available to every project of this user.
+ */
+pub fn get_user_dir() -> PathBuf {
+    get_config_dir().join("templates")
+}
+
+/*
+ * Gets the directory that holds the templates shared by everyone working on the current project.
+ */
+pub fn get_project_dir() -> PathBuf {
+    get_cwd().join("templates")
+}
+
+/*
+ * A template that is ready to be applied to a new component.
+ */
+pub struct Template {
+    pub dir: PathBuf,
+    downloaded: bool,
+}
+
+impl Template {
+    /*
+     * Deletes the copy of a template that was downloaded from a repository. Templates kept in the
+     * project, the user's templates or any other directory are left alone.
+     */
+    pub fn clean_up(self) {
+        if self.downloaded {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+/*
+ * Finds the template directory for a template name, a path to a template directory, or the URL
+ * of a repository holding a template. Templates from URLs are downloaded to a temporary directory.
+ */
+pub fn resolve(template: &str) -> Result<Template, String> {
+    if is_url(template) {
+        let dest = get_temp_dir("template");
+
+        fetch(template, &dest)?;
+
+        return Ok(Template {
+            dir: dest,
+            downloaded: true,
+        });
+    }
+
+    // Project templates take priority over user templates with the same name
+    for dir in &[get_project_dir(), get_user_dir()] {
+        if dir.join(template).is_dir() {
+            return Ok(Template {
+                dir: dir.join(template),
+                downloaded: false,
+            });
+        }
+    }
+
+    if Path::new(template).is_dir() {
+        return Ok(Template {
+            dir: get_cwd().join(template),
+            downloaded: false,
+        });
+    }
+
+    Err(format!(
+        "There is no template named {}, and it is not a directory or a repository URL.",
+        template
+    ))
+}
+
+/*
+ * Copies the files of a template into a newly created component, filling in the placeholders
+ * in file names and text files. Template files replace the ones the component was created with.
+ */
+pub fn apply(
+    template_dir: &Path,
+    component_path: &Path,
+    values: &[(&str, String)],
+) -> Result<(), String> {
+    let entries = fs::read_dir(template_dir)
+        .map_err(|e| format!("Could not read template {}: {}", template_dir.display(), e))?;
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        // Templates that come from repositories carry git's files with them
+        if file_name == ".git" {
+            continue;
+        }
+
+        let target = component_path.join(fill_placeholders(&file_name, values));
+
+        if entry.path().is_dir() {
+            fs::create_dir_all(&target)
+                .map_err(|e| format!("Could not create {}: {}", target.display(), e))?;
+
+            apply(&entry.path(), &target, values)?;
+        } else {
+            let contents = fs::read(entry.path())
+                .map_err(|e| format!("Could not read {}: {}", entry.path().display(), e))?;
+
+            // Binary files such as CAD models and images are copied as they are
+            let contents = match String::from_utf8(contents) {
+                Ok(text) => fill_placeholders(&text, values).into_bytes(),
+                Err(e) => e.into_bytes(),
+            };
+
+            fs::write(&target, contents)
+                .map_err(|e| format!("Could not write {}: {}", target.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/*
+ * Gets the placeholder values that are filled into a template for a new component.
+ */
+pub fn get_values(
+    name: &str,
+    source_license: &str,
+    doc_license: &str,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("name", name.to_string()),
+        ("source_license", source_license.to_string()),
+        ("doc_license", doc_license.to_string()),
+        ("author", get_author()),
+        ("date", component::get_date(component::get_timestamp())),
+    ]
+}
+
+/*
+ * Lists the templates that are available, along with where each one is kept.
+ */
+pub fn list() -> Vec<(String, String)> {
+    let mut templates: Vec<(String, String)> = Vec::new();
+
+    for (scope, dir) in &[("project", get_project_dir()), ("user", get_user_dir())] {
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                if entry.path().is_dir() {
+                    templates.push((
+                        entry.file_name().to_string_lossy().to_string(),
+                        scope.to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    templates.sort();
+
+    templates
+}
+
+/*
+ * Adds a template from a directory or repository URL to the user or project templates.
+ */
+pub fn add(source: &str, name: &str, project: bool) -> Result<(), String> {
+    let dest = get_scope_dir(project).join(name);
+
+    if dest.exists() {
+        return Err(format!("A template named {} already exists.", name));
+    }
+
+    fs::create_dir_all(dest.parent().unwrap())
+        .map_err(|e| format!("Could not create the templates directory: {}", e))?;
+
+    if is_url(source) {
+        fetch(source, &dest)
+    } else {
+        let source_dir = get_cwd().join(source);
+
+        if !source_dir.is_dir() {
+            return Err(format!("{} is not a directory.", source));
+        }
+
+        component::copy_dir(&source_dir, &dest)
+            .map_err(|e| format!("Could not copy the template: {}", e))
+    }
+}
+
+/*
+ * Removes a template from the user or project templates.
+ */
+pub fn remove(name: &str, project: bool) -> Result<(), String> {
+    let dir = get_scope_dir(project).join(name);
+
+    if !dir.is_dir() {
+        return Err(format!("There is no template named {}.", name));
+    }
+
+    fs::remove_dir_all(&dir).map_err(|e| format!("Could not remove the template: {}", e))
+}
+
+/*
+ * Works out a name for a template from the directory or URL it is being added from.
+ */
+pub fn get_default_name(source: &str) -> String {
+    let source = source.trim_end_matches('/').trim_end_matches('\\');
+    let last = source.rsplit(&['/', '\\'][..]).next().unwrap_or(source);
+
+    last.trim_end_matches(".git").to_string()
+}
+
+/*
+ * Downloads a template from a repository, without the history of the repository.
+ */
+fn fetch(url: &str, dest: &Path) -> Result<(), String> {
+    tools::git(
+        &get_cwd(),
+        &["clone", "--depth", "1", url, &dest.to_string_lossy()],
+    )?;
+
+    fs::remove_dir_all(dest.join(".git"))
+        .map_err(|e| format!("Could not clean up the downloaded template: {}", e))
+}
+
+/*
+ * Replaces each {{placeholder}} in some text with its value.
+ */
+fn fill_placeholders(text: &str, values: &[(&str, String)]) -> String {
+    let mut text = text.to_string();
+
+    for &(key, ref value) in values {
+        text = text.replace(&format!("{{{{{}}}}}", key), value);
+    }
+
+    text
+}
+
+/*
+ * Gets the directory for either the project or the user templates.
+ */
+fn get_scope_dir(project: bool) -> PathBuf {
+    if project {
+        get_project_dir()
+    } else {
+        get_user_dir()
+    }
+}
+
+/*
+ * Tells whether a template source is a repository URL rather than a name or directory.
+ */
+pub fn is_url(source: &str) -> bool {
+    source.contains("://") || source.starts_with("git@")
+}
+
+/*
+ * Gets the name of the person creating the component from their git settings.
+ */
+fn get_author() -> String {
+    match tools::git(&get_cwd(), &["config", "user.name"]) {
+        Ok(name) => name.trim().to_string(),
+        Err(_) => env::var("USER")
+            .or_else(|_| env::var("USERNAME"))
+            .unwrap_or_default(),
+    }
+}
@@ -0,0 +1,91 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/*
+ * Runs git in a directory, giving back standard output on success and standard error on failure.
+ */
+pub fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    run("git", dir, args)
+}
+
+/*
+ * Tells whether git can take configuration from GIT_CONFIG_COUNT and the variables that go with
+ * it, which git added in version 2.31. Offline mode and Git LFS rely on it.
+ */
+pub fn git_reads_env_config() -> bool {
+    let version = match git(Path::new("."), &["--version"]) {
+        Ok(version) => version,
+        Err(_) => return false,
+    };
+
+    // The version comes as git version 2.39.2, with extra details on some platforms
+    let numbers: Vec<u32> = version
+        .split_whitespace()
+        .nth(2)
+        .unwrap_or("")
+        .split('.')
+        .take(2)
+        .filter_map(|part| part.parse().ok())
+        .collect();
+
+    match numbers.as_slice() {
+        [major, minor] => (*major, *minor) >= (2, 31),
+        _ => false,
+    }
+}
+
+/*
+ * Runs npm in a directory, giving back standard output on success and standard error on failure.
+ */
+pub fn npm(dir: &Path, args: &[&str]) -> Result<String, String> {
+    // npm is a batch file on Windows, and Command does not look for those on its own
+    let program = if cfg!(windows) { "npm.cmd" } else { "npm" };
+
+    run(program, dir, args)
+}
+
+/*
+ * Runs curl, giving back standard output on success and standard error on failure. The config is
+ * given to curl on standard input, so that secrets such as access tokens are not shown to other
+ * users in the list of running processes.
+ */
+pub fn curl(args: &[&str], config: &str) -> Result<String, String> {
+    let child = Command::new("curl")
+        .args(&["-K", "-"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return Err(format!("Could not run curl: {}", e)),
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.as_bytes())
+            .map_err(|e| format!("Could not run curl: {}", e))?;
+    }
+
+    match child.wait_with_output() {
+        Ok(ref out) if out.status.success() => Ok(String::from_utf8_lossy(&out.stdout).to_string()),
+        Ok(out) => Err(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+        Err(e) => Err(format!("Could not run curl: {}", e)),
+    }
+}
+
+/*
+ * Runs an external program and collects its output.
+ */
+fn run(program: &str, dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program).args(args).current_dir(dir).output();
+
+    match output {
+        Ok(ref out) if out.status.success() => Ok(String::from_utf8_lossy(&out.stdout).to_string()),
+        Ok(out) => Err(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+        Err(e) => Err(format!("Could not run {}: {}", program, e)),
+    }
+}
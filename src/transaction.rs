@@ -0,0 +1,150 @@
+use component;
+use get_temp_dir;
+use sliderule::SROutput;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/*
+ * A snapshot of the parts of a project that an operation can change, so that they can be put back
+ * the way they were if the operation fails partway through.
+ */
+pub struct Transaction {
+    project_path: PathBuf,
+    backup_dir: PathBuf,
+    entries: Vec<(PathBuf, bool)>,
+    installed: Vec<String>,
+}
+
+impl Transaction {
+    /*
+     * Takes a snapshot of the project files, and of the directories of the named component, before
+     * an operation is run on the project.
+     */
+    pub fn begin(project_path: &Path, name: &str) -> Result<Transaction, String> {
+        let mut paths = vec![
+            project_path.join("package.json"),
+            project_path.join("package-lock.json"),
+            project_path.join(".sr"),
+        ];
+
+        // Names that come from URLs or paths cannot be used to find the component's directories
+        if !name.is_empty() && !name.contains('/') && !name.contains('\\') {
+            // Local components can be nested inside of other local components
+            paths.push(
+                component::find_local(project_path, name)
+                    .unwrap_or_else(|| project_path.join("components").join(name)),
+            );
+            paths.push(project_path.join("node_modules").join(name));
+        }
+
+        Transaction::begin_with(project_path, &paths)
+    }
+
+    /*
+     * Takes a snapshot of the given files and directories before an operation is run on the project.
+     */
+    pub fn begin_with(project_path: &Path, paths: &[PathBuf]) -> Result<Transaction, String> {
+        let backup_dir = get_temp_dir("transaction");
+        let mut entries: Vec<(PathBuf, bool)> = Vec::new();
+
+        fs::create_dir_all(&backup_dir).map_err(|e| {
+            format!(
+                "Could not create a directory for the project snapshot: {}",
+                e
+            )
+        })?;
+
+        for (index, path) in paths.iter().enumerate() {
+            // Linked components point at work that is kept elsewhere, so they are left alone
+            if component::is_link(path) {
+                continue;
+            }
+
+            let existed = path.exists();
+
+            if existed {
+                copy_path(path, &backup_dir.join(index.to_string())).map_err(|e| {
+                    format!("Could not take a snapshot of {}: {}", path.display(), e)
+                })?;
+            }
+
+            entries.push((path.to_path_buf(), existed));
+        }
+
+        Ok(Transaction {
+            project_path: project_path.to_path_buf(),
+            backup_dir,
+            entries,
+            installed: list_installed(project_path),
+        })
+    }
+
+    /*
+     * Keeps the changes that the operation made and throws away the snapshot.
+     */
+    pub fn commit(self) {
+        let _ = fs::remove_dir_all(&self.backup_dir);
+    }
+
+    /*
+     * Puts the project back the way it was when the snapshot was taken.
+     */
+    pub fn rollback(self) -> Result<(), String> {
+        for (index, &(ref path, existed)) in self.entries.iter().enumerate() {
+            component::remove_path(path)
+                .map_err(|e| format!("Could not remove {}: {}", path.display(), e))?;
+
+            if existed {
+                copy_path(&self.backup_dir.join(index.to_string()), path)
+                    .map_err(|e| format!("Could not restore {}: {}", path.display(), e))?;
+            }
+        }
+
+        // The name of an added component is not known ahead of time, so remove whatever npm installed
+        for name in list_installed(&self.project_path) {
+            if !self.installed.contains(&name) {
+                let path = self.project_path.join("node_modules").join(&name);
+
+                component::remove_path(&path)
+                    .map_err(|e| format!("Could not remove {}: {}", path.display(), e))?;
+            }
+        }
+
+        let _ = fs::remove_dir_all(&self.backup_dir);
+
+        Ok(())
+    }
+}
+
+/*
+ * Tells whether a Sliderule operation failed, either in Sliderule itself or in the git or npm
+ * process that it ran.
+ */
+pub fn has_failed(output: &SROutput) -> bool {
+    output.status != 0 || output.wrapped_status != 0
+}
+
+/*
+ * Lists what is installed in the top level of node_modules.
+ */
+fn list_installed(project_path: &Path) -> Vec<String> {
+    match fs::read_dir(project_path.join("node_modules")) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/*
+ * Copies a file, or a directory and everything in it.
+ */
+fn copy_path(src: &Path, dest: &Path) -> io::Result<()> {
+    if src.is_dir() {
+        component::copy_dir(src, dest)
+    } else {
+        fs::copy(src, dest).map(|_| ())
+    }
+}
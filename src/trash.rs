@@ -0,0 +1,284 @@
+use cache;
+use component;
+use sliderule;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tools;
+use transaction;
+
+/*
+ * The project directory that removed components are kept in until the trash is emptied.
+ */
+const TRASH_DIR: &str = ".sr_trash";
+
+/*
+ * A removed component that is being kept in the trash.
+ */
+pub struct Entry {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub path: String,
+    pub dependency: String,
+    pub removed: u64,
+}
+
+/*
+ * Puts a copy of a component that is about to be removed into the project's trash. Local
+ * components are copied whole, and remote components are recorded by their package.json entry.
+ * Returns the ID of the trash entry, or None if there is no component with that name.
+ */
+pub fn trash_component(project_path: &Path, name: &str) -> Result<Option<String>, String> {
+    // Local components can be inside of other local components, the same as when they are removed
+    let local_path = component::find_local(project_path, name)
+        .unwrap_or_else(|| project_path.join("components").join(name));
+    let dependency = component::get_dependencies(project_path)
+        .into_iter()
+        .find(|dependency| dependency.0 == name)
+        .map(|dependency| dependency.1);
+
+    let kind = if local_path.is_dir() {
+        "local"
+    } else if dependency.is_some() {
+        "remote"
+    } else {
+        return Ok(None);
+    };
+
+    let removed = component::get_timestamp();
+
+    // Removals made within the same second are told apart by a counter
+    let mut id = format!("{}_{}", name, removed);
+    let mut count = 1;
+    while get_entry_dir(project_path, &id).exists() {
+        id = format!("{}_{}_{}", name, removed, count);
+        count += 1;
+    }
+    let entry_dir = get_entry_dir(project_path, &id);
+
+    fs::create_dir_all(&entry_dir)
+        .map_err(|e| format!("Could not create the trash directory: {}", e))?;
+
+    ignore_trash(project_path)?;
+
+    if kind == "local" {
+        component::copy_dir(&local_path, &entry_dir.join("component"))
+            .map_err(|e| format!("Could not copy {} into the trash: {}", name, e))?;
+    }
+
+    let metadata = format!(
+        "name: {}\nkind: {}\npath: {}\ndependency: {}\nremoved: {}\n",
+        name,
+        kind,
+        get_relative(project_path, &local_path),
+        dependency.unwrap_or_default(),
+        removed
+    );
+
+    fs::write(entry_dir.join("metadata"), metadata)
+        .map_err(|e| format!("Could not write the trash metadata for {}: {}", name, e))?;
+
+    Ok(Some(id))
+}
+
+/*
+ * Deletes an entry from the trash, such as when the removal it was made for did not happen.
+ */
+pub fn discard(project_path: &Path, id: &str) {
+    let _ = fs::remove_dir_all(get_entry_dir(project_path, id));
+}
+
+/*
+ * Lists the components in the trash, with the most recently removed ones first.
+ */
+pub fn list(project_path: &Path) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = match fs::read_dir(project_path.join(TRASH_DIR)) {
+        Ok(dirs) => dirs
+            .filter_map(|e| e.ok())
+            .filter_map(|dir| read_entry(&dir.path()))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    entries.sort_by(|a, b| {
+        b.removed
+            .cmp(&a.removed)
+            .then_with(|| get_counter(b).cmp(&get_counter(a)))
+    });
+
+    entries
+}
+
+/*
+ * Puts the most recently removed component with the given name back into the project, and takes
+ * it out of the trash.
+ */
+pub fn restore(project_path: &Path, name: &str) -> Result<(), String> {
+    let entry = list(project_path)
+        .into_iter()
+        .find(|entry| entry.name == name)
+        .ok_or_else(|| format!("There is no component named {} in the trash.", name))?;
+
+    if entry.kind == "local" {
+        let dest = project_path.join(&entry.path);
+
+        if dest.exists() {
+            return Err(format!(
+                "A component named {} already exists in this project.",
+                name
+            ));
+        }
+
+        if !dest.parent().map(|parent| parent.exists()).unwrap_or(false) {
+            return Err(format!(
+                "The component that held {} is no longer in this project, so {} cannot be put back at {}.",
+                name, name, entry.path
+            ));
+        }
+
+        component::copy_dir(
+            &get_entry_dir(project_path, &entry.id).join("component"),
+            &dest,
+        )
+        .map_err(|e| format!("Could not restore {}: {}", name, e))?;
+    } else {
+        // Remote components are installed again from the location they were removed from, at the same commit
+        let output = sliderule::add_remote_component(
+            project_path,
+            &entry.dependency,
+            Some(
+                cache::get_cache_dir()
+                    .join("npm")
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+        );
+
+        if transaction::has_failed(&output) {
+            return Err(format!(
+                "Could not install {} again: {}",
+                name,
+                output.stderr.join(" ").trim()
+            ));
+        }
+    }
+
+    discard(project_path, &entry.id);
+
+    Ok(())
+}
+
+/*
+ * Permanently deletes everything in the trash.
+ */
+pub fn empty(project_path: &Path) -> Result<(), String> {
+    let trash_dir = project_path.join(TRASH_DIR);
+
+    if !trash_dir.exists() {
+        return Ok(());
+    }
+
+    fs::remove_dir_all(&trash_dir).map_err(|e| format!("Could not empty the trash: {}", e))
+}
+
+/*
+ * Reads the metadata of a trash entry.
+ */
+fn read_entry(entry_dir: &Path) -> Option<Entry> {
+    let contents = fs::read_to_string(entry_dir.join("metadata")).ok()?;
+
+    let get_value = |key: &str| {
+        contents
+            .lines()
+            .find(|line| line.starts_with(&format!("{}:", key)))
+            .map(|line| line[key.len() + 1..].trim().to_string())
+    };
+
+    Some(Entry {
+        id: entry_dir.file_name()?.to_string_lossy().to_string(),
+        name: get_value("name")?,
+        kind: get_value("kind")?,
+        // Entries from before nested components were trashed were always directly under components
+        path: get_value("path")
+            .unwrap_or_else(|| format!("components/{}", get_value("name").unwrap_or_default())),
+        dependency: get_value("dependency").unwrap_or_default(),
+        removed: get_value("removed")?.parse().ok()?,
+    })
+}
+
+/*
+ * Gets a path relative to the project, with forward slashes so that it reads the same on every platform.
+ */
+fn get_relative(project_path: &Path, path: &Path) -> String {
+    path.strip_prefix(project_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/*
+ * Gets the directory of a trash entry.
+ */
+fn get_entry_dir(project_path: &Path, id: &str) -> PathBuf {
+    project_path.join(TRASH_DIR).join(id)
+}
+
+/*
+ * Gets the counter that tells apart entries for removals made within the same second.
+ */
+fn get_counter(entry: &Entry) -> u32 {
+    // IDs are <name>_<removed>, with _<counter> added for the second and later removals
+    entry.id[..]
+        .strip_prefix(&format!("{}_{}_", entry.name, entry.removed))
+        .and_then(|counter| counter.parse().ok())
+        .unwrap_or(0)
+}
+
+/*
+ * Makes sure that git does not upload the trash along with the rest of the project. The trash is
+ * listed in the repository's own exclude file, so that the tracked .gitignore is not changed by
+ * every removal. Projects without a repository yet use .gitignore instead.
+ */
+fn ignore_trash(project_path: &Path) -> Result<(), String> {
+    let ignore_file = if project_path.join(".git").exists() {
+        // The repository's directory can be elsewhere, such as for worktrees
+        let exclude = tools::git(project_path, &["rev-parse", "--git-path", "info/exclude"])?;
+
+        project_path.join(exclude.trim())
+    } else {
+        project_path.join(".gitignore")
+    };
+
+    let contents = fs::read_to_string(&ignore_file).unwrap_or_default();
+
+    if contents
+        .lines()
+        .any(|line| line.trim().trim_matches('/') == TRASH_DIR)
+    {
+        return Ok(());
+    }
+
+    let mut new_contents = contents;
+    if !new_contents.is_empty() && !new_contents.ends_with('\n') {
+        new_contents.push('\n');
+    }
+    new_contents.push_str(&format!("{}/\n", TRASH_DIR));
+
+    if let Some(parent) = ignore_file.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            format!(
+                "Could not add the trash to {}: {}",
+                ignore_file.display(),
+                e
+            )
+        })?;
+    }
+
+    fs::write(&ignore_file, new_contents).map_err(|e| {
+        format!(
+            "Could not add the trash to {}: {}",
+            ignore_file.display(),
+            e
+        )
+    })
+}
@@ -0,0 +1,226 @@
+use component;
+use get_temp_dir;
+use std::fs;
+use std::path::Path;
+use tools;
+
+const SOURCES_HEADER: &str =
+    "# Remote components vendored into this directory: name, origin URL, commit";
+
+/*
+ * Copies an installed remote component from node_modules into the project's vendor directory, and
+ * points the project's package.json at the copy so that npm installs it from there instead of from
+ * its repository. Where the component came from is recorded in vendor/sources.txt.
+ */
+pub fn vendor_component(project_path: &Path, name: &str) -> Result<(), String> {
+    let installed_dir = project_path.join("node_modules").join(name);
+    let vendor_dir = project_path.join("vendor").join(name);
+
+    let location = match component::get_dependencies(project_path)
+        .into_iter()
+        .find(|dependency| dependency.0 == name)
+    {
+        Some(dependency) => dependency.1,
+        None => {
+            return Err(format!(
+                "{} is not a remote component of this project.",
+                name
+            ))
+        }
+    };
+
+    // The installed copy of a vendored component is the vendored copy itself
+    if location == get_location(name) {
+        return Err(format!(
+            "{} is already vendored. Run vendor --sync {} to update it from its repository.",
+            name, name
+        ));
+    }
+
+    if !installed_dir.exists() {
+        return Err(format!(
+            "The remote component {} is not installed. Run download dependencies first.",
+            name
+        ));
+    }
+
+    // A copy left behind from an earlier time it was vendored is replaced whole
+    if vendor_dir.exists() {
+        fs::remove_dir_all(&vendor_dir)
+            .map_err(|e| format!("Could not remove the old copy of {}: {}", name, e))?;
+    }
+
+    // Linked components are checkouts with a repository of their own, which is not part of the copy
+    component::copy_dir_except(&installed_dir, &vendor_dir, &[".git"])
+        .map_err(|e| format!("Could not copy {} into the vendor directory: {}", name, e))?;
+
+    let url = component::get_git_url(&location);
+    let commit =
+        component::get_locked_commit(project_path, name).unwrap_or_else(|| String::from("unknown"));
+
+    record_source(project_path, name, &url, &commit)?;
+
+    use_vendored_copy(project_path, name)
+}
+
+/*
+ * Replaces the vendored copy of a remote component with the latest version from the repository
+ * recorded for it in vendor/sources.txt, and records the commit that the copy now comes from.
+ * Returns the commits that the old and new copies came from.
+ */
+pub fn sync_component(project_path: &Path, name: &str) -> Result<(String, String), String> {
+    let vendor_dir = project_path.join("vendor").join(name);
+
+    let (url, old_commit) = match read_source(project_path, name) {
+        Some(source) => source,
+        None => {
+            return Err(format!(
+                "{} is not listed in vendor/sources.txt. Vendor it first.",
+                name
+            ))
+        }
+    };
+
+    let clone_dir = get_temp_dir("vendor");
+    let result = fetch(&url, &clone_dir).and_then(|new_commit| {
+        // Starting from a clean copy makes sure that files removed upstream do not linger
+        if vendor_dir.exists() {
+            fs::remove_dir_all(&vendor_dir)
+                .map_err(|e| format!("Could not remove the old copy of {}: {}", name, e))?;
+        }
+
+        component::copy_dir_except(&clone_dir, &vendor_dir, &[".git"])
+            .map_err(|e| format!("Could not copy {} into the vendor directory: {}", name, e))?;
+
+        Ok(new_commit)
+    });
+    let _ = fs::remove_dir_all(&clone_dir);
+
+    let new_commit = result?;
+
+    record_source(project_path, name, &url, &new_commit)?;
+
+    Ok((old_commit, new_commit))
+}
+
+/*
+ * Lists the names of the components recorded in vendor/sources.txt.
+ */
+pub fn list_vendored(project_path: &Path) -> Vec<String> {
+    read_sources(project_path)
+        .iter()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/*
+ * Lists the names of the remote components that are installed in node_modules and have not been
+ * vendored yet.
+ */
+pub fn list_installed(project_path: &Path) -> Vec<String> {
+    component::get_dependencies(project_path)
+        .into_iter()
+        .filter(|dependency| dependency.1 != get_location(&dependency.0))
+        .map(|dependency| dependency.0)
+        .filter(|name| project_path.join("node_modules").join(name).exists())
+        .collect()
+}
+
+/*
+ * Gets the package.json location of a vendored component.
+ */
+fn get_location(name: &str) -> String {
+    format!("file:vendor/{}", name)
+}
+
+/*
+ * Changes the project's dependency on a remote component to the vendored copy of it.
+ */
+fn use_vendored_copy(project_path: &Path, name: &str) -> Result<(), String> {
+    let package_file = project_path.join("package.json");
+
+    let contents = fs::read_to_string(&package_file)
+        .map_err(|e| format!("Could not read {}: {}", package_file.display(), e))?;
+
+    let dependencies: Vec<(String, String)> = component::parse_dependencies(&contents)
+        .into_iter()
+        .map(|(dependency, location)| {
+            if dependency == name {
+                (dependency, get_location(name))
+            } else {
+                (dependency, location)
+            }
+        })
+        .collect();
+
+    fs::write(
+        &package_file,
+        component::set_dependencies(&contents, &dependencies),
+    )
+    .map_err(|e| format!("Could not update {}: {}", package_file.display(), e))
+}
+
+/*
+ * Clones the latest version of a repository into a directory. Returns the commit that was cloned.
+ */
+fn fetch(url: &str, clone_dir: &Path) -> Result<String, String> {
+    tools::git(
+        Path::new("."),
+        &["clone", "--quiet", url, &clone_dir.to_string_lossy()],
+    )
+    .map_err(|e| format!("Could not download {}: {}", url, e))?;
+
+    tools::git(clone_dir, &["rev-parse", "HEAD"]).map(|commit| commit.trim().to_string())
+}
+
+/*
+ * Reads the lines of vendor/sources.txt that record a vendored component.
+ */
+fn read_sources(project_path: &Path) -> Vec<String> {
+    match fs::read_to_string(project_path.join("vendor").join("sources.txt")) {
+        Ok(contents) => contents
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/*
+ * Gets the origin URL and commit recorded for a vendored component in vendor/sources.txt.
+ */
+fn read_source(project_path: &Path, name: &str) -> Option<(String, String)> {
+    read_sources(project_path).iter().find_map(|line| {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+
+        match parts.as_slice() {
+            [record_name, url, commit] if *record_name == name => {
+                Some((url.to_string(), commit.to_string()))
+            }
+            _ => None,
+        }
+    })
+}
+
+/*
+ * Writes the origin URL and commit of a vendored component to the vendor/sources.txt file.
+ */
+fn record_source(project_path: &Path, name: &str, url: &str, commit: &str) -> Result<(), String> {
+    let sources_file = project_path.join("vendor").join("sources.txt");
+
+    // Keep the records for every other component that has been vendored
+    let mut records: Vec<String> = read_sources(project_path)
+        .into_iter()
+        .filter(|line| line.split_whitespace().next() != Some(name))
+        .collect();
+
+    records.push(format!("{} {} {}", name, url, commit));
+    records.sort();
+
+    let contents = format!("{}\n{}\n", SOURCES_HEADER, records.join("\n"));
+
+    fs::write(&sources_file, contents)
+        .map_err(|e| format!("Could not write {}: {}", sources_file.display(), e))
+}
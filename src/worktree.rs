@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use tools;
+
+/*
+ * Lists the files of a component that have been changed, added or deleted since the last upload.
+ */
+pub fn list_uncommitted(component_path: &Path) -> Vec<String> {
+    // Components that have never been uploaded have nothing to download changes into
+    match tools::git(
+        component_path,
+        &["status", "--porcelain", "--untracked-files=all"],
+    ) {
+        Ok(status) => status
+            .lines()
+            .filter(|line| line.len() > 3)
+            .map(|line| line[3..].to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/*
+ * Counts the commits that have been made locally but not uploaded yet. None means that git could
+ * not tell, such as when the branch has never been uploaded.
+ */
+pub fn count_unpushed(component_path: &Path) -> Option<usize> {
+    tools::git(
+        component_path,
+        &["rev-list", "--count", "@{upstream}..HEAD"],
+    )
+    .ok()
+    .and_then(|count| count.trim().parse().ok())
+}
+
+/*
+ * Sets the uncommitted changes of a component aside so that changes can be downloaded cleanly.
+ */
+pub fn stash(component_path: &Path) -> Result<(), String> {
+    tools::git(
+        component_path,
+        &[
+            "stash",
+            "push",
+            "--include-untracked",
+            "-m",
+            "Changes set aside by sliderule-cli download",
+        ],
+    )
+    .map(|_| ())
+}
+
+/*
+ * Puts the changes that were set aside by stash back into the component.
+ */
+pub fn unstash(component_path: &Path) -> Result<(), String> {
+    tools::git(component_path, &["stash", "pop"]).map(|_| ())
+}
+
+/*
+ * Lists the files that git could not merge, grouped by the local component that each belongs to.
+ * The component that the command was run in is listed as ".".
+ */
+pub fn list_conflicts(component_path: &Path) -> BTreeMap<String, Vec<String>> {
+    let mut conflicts: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    let files =
+        tools::git(component_path, &["diff", "--name-only", "--diff-filter=U"]).unwrap_or_default();
+
+    for file in files.lines().filter(|line| !line.trim().is_empty()) {
+        let (owner, rest) = split_component(file.trim());
+
+        conflicts.entry(owner).or_default().push(rest);
+    }
+
+    conflicts
+}
+
+/*
+ * Splits a file path into the local component it belongs to and its path within that component.
+ */
+fn split_component(file: &str) -> (String, String) {
+    let parts: Vec<&str> = file.split('/').collect();
+    let mut split_at = 0;
+
+    // The deepest components/<name> pair that still leaves a file name after it is the owner
+    for index in 0..parts.len() {
+        if parts[index] == "components" && index + 2 < parts.len() {
+            split_at = index + 2;
+        }
+    }
+
+    if split_at == 0 {
+        (String::from("."), file.to_string())
+    } else {
+        (parts[..split_at].join("/"), parts[split_at..].join("/"))
+    }
+}
@@ -75,11 +75,1605 @@ mod management {
         ));
     }
 
+    #[test]
+    /*
+     * Makes sure that a component created from a template gets the template's files with the placeholders filled in.
+     */
+    fn test_create_from_template() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Make a simple template that uses placeholders in both file names and contents
+        let template_dir = test_dir.join("pcb_template");
+        fs::create_dir_all(template_dir.join("source"))
+            .expect("Failed to create template directory.");
+        fs::write(
+            template_dir.join("README.md"),
+            "# {{name}}\nNew Sliderule component.\nLicensed {{source_license}}\n",
+        )
+        .expect("Failed to write template README.");
+        fs::write(template_dir.join("source").join("{{name}}.kicad_pro"), "")
+            .expect("Failed to write template source file.");
+
+        let output = Command::new(&cmd_path)
+            .args(&[
+                "create",
+                "-s",
+                "NotASourceLicense",
+                "-d",
+                "NotADocLicense",
+                "--template",
+                &template_dir.to_string_lossy(),
+                "board",
+            ])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "Component creation finished."
+        );
+
+        let component_dir = test_dir.join("toplevel").join("components").join("board");
+
+        // The standard structure should still be there underneath the template
+        assert!(component_dir.join("bom_data.yaml").exists());
+        assert!(component_dir.join("docs").exists());
+
+        // The template's files should have their placeholders filled in
+        assert!(file_contains_content(
+            &component_dir.join("README.md"),
+            2,
+            "Licensed NotASourceLicense",
+        ));
+        assert!(component_dir
+            .join("source")
+            .join("board.kicad_pro")
+            .exists());
+    }
+
+    #[test]
+    /*
+     * Makes sure that an existing directory can be turned into a component without losing its files.
+     */
+    fn test_init_existing_directory() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Simulate a legacy project with its own README and design files
+        let legacy_dir = test_dir.join("legacy");
+        fs::create_dir(&legacy_dir).expect("Failed to create legacy directory.");
+        fs::write(legacy_dir.join("README.md"), "# Legacy project\n")
+            .expect("Failed to write legacy README.");
+        fs::write(legacy_dir.join("board.kicad_pcb"), "")
+            .expect("Failed to write legacy design file.");
+
+        let output = Command::new(&cmd_path)
+            .args(&[
+                "init",
+                "-s",
+                "NotASourceLicense",
+                "-d",
+                "NotADocLicense",
+                "--name",
+                "legacy_board",
+                "--move-files",
+            ])
+            .current_dir(&legacy_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "Component initialization finished."
+        );
+
+        // The missing parts of the component should have been added
+        assert!(legacy_dir.join(".sr").exists());
+        assert!(legacy_dir.join("bom_data.yaml").exists());
+        assert!(legacy_dir.join("docs").exists());
+        assert!(legacy_dir.join("components").exists());
+        assert!(file_contains_content(
+            &legacy_dir.join("package.json"),
+            9999,
+            "\"name\": \"legacy_board\",",
+        ));
+
+        // The existing README should not have been replaced, and the design file should be in source
+        assert!(file_contains_content(
+            &legacy_dir.join("README.md"),
+            0,
+            "# Legacy project",
+        ));
+        assert!(legacy_dir.join("source").join("board.kicad_pcb").exists());
+        assert!(!legacy_dir.join("board.kicad_pcb").exists());
+    }
+
     #[test]
     /*
      * Tests the ability to download (clone) a component from a repo.
      */
-    fn test_download_component() {
+    fn test_download_component() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Try to download the component
+        let output = Command::new(cmd_path)
+            .args(&["download", "https://github.com/jmwright/blink_firmware.git"])
+            .current_dir(&test_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component download finished."));
+
+        assert!(is_valid_component(
+            &test_dir.join("blink_firmware"),
+            "blink_firmware",
+            "Unlicense",
+            "CC0-1.0",
+        ));
+    }
+
+    #[test]
+    /*
+     * Tests the ability to download remote changes for a component
+     */
+    fn test_download_component_changes() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Try to download the component's remote changes
+        let output = Command::new(cmd_path)
+            .args(&["download", "all"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component download finished."));
+    }
+
+    /*
+     * Tests the ability to download changes to the remote components housed within a component/project
+     */
+    #[test]
+    fn test_download_dependencies() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Try to download the component's remote changes
+        let output = Command::new(cmd_path)
+            .args(&["download", "dependencies"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout)
+            .contains("Component download of dependencies only finished."));
+    }
+
+    #[test]
+    /*
+     * Tests the addition and removal of a remote component.
+     */
+    fn test_add_remove_component() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // The add command
+        let add_output = Command::new(&cmd_path)
+            .args(&["add", "https://github.com/jmwright/arduino-sr.git"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&add_output.stdout).contains("Component add finished."));
+
+        // Make sure that the newly installed remote component is actually there
+        assert!(
+            &test_dir
+                .join("toplevel")
+                .join("node_modules")
+                .join("arduino-sr")
+                .exists(),
+            "arduino-sr component directory does not exist."
+        );
+
+        // Make sure the component is valid
+        assert!(is_valid_component(
+            &test_dir
+                .join("toplevel")
+                .join("node_modules")
+                .join("arduino-sr"),
+            "arduino-sr",
+            "Unlicense",
+            "CC0-1.0",
+        ));
+
+        // The remove command
+        let remove_output = Command::new(&cmd_path)
+            .args(&["remove", "-y", "arduino-sr"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(
+            String::from_utf8_lossy(&remove_output.stdout).contains("Component remove finished.")
+        );
+
+        // Make sure that the newly removed remote component was actually removed
+        assert!(
+            !&test_dir
+                .join("toplevel")
+                .join("node_modules")
+                .join("arduino-sr")
+                .exists(),
+            "arduino-sr component directory does not exist."
+        );
+    }
+
+    #[test]
+    /*
+     * Tests adding a component from a directory on the local filesystem instead of a URL.
+     */
+    fn test_add_local_component() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Create a component outside of the project to act as the local source
+        let output = Command::new(&cmd_path)
+            .args(&[
+                "create",
+                "-s",
+                "NotASourceLicense",
+                "-d",
+                "NotADocLicense",
+                "localsrc",
+            ])
+            .current_dir(&test_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "Component creation finished."
+        );
+
+        // The add command, using a file:// URL
+        let add_output = Command::new(&cmd_path)
+            .args(&[
+                "add",
+                &format!("file://{}", test_dir.join("localsrc").display()),
+            ])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&add_output.stdout).contains("Component add finished."));
+
+        // Make sure that the newly installed component is actually there
+        assert!(
+            test_dir
+                .join("toplevel")
+                .join("node_modules")
+                .join("localsrc")
+                .exists(),
+            "localsrc component directory does not exist."
+        );
+    }
+
+    #[test]
+    /*
+     * Tests adding a component from a tarball on the local filesystem.
+     */
+    fn test_add_archive_component() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Create a component outside of the project and pack it up the way it would be shared
+        Command::new(&cmd_path)
+            .args(&[
+                "create",
+                "-s",
+                "NotASourceLicense",
+                "-d",
+                "NotADocLicense",
+                "archivesrc",
+            ])
+            .current_dir(&test_dir)
+            .output()
+            .expect("failed to execute process");
+
+        Command::new("tar")
+            .args(&["-czf", "archivesrc.tgz", "archivesrc"])
+            .current_dir(&test_dir)
+            .output()
+            .expect("failed to execute tar");
+
+        let add_output = Command::new(&cmd_path)
+            .args(&["add", &test_dir.join("archivesrc.tgz").to_string_lossy()])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&add_output.stdout).contains("Component add finished."));
+
+        // The component is checked inside of the archive and then installed from it
+        assert!(
+            test_dir
+                .join("toplevel")
+                .join("node_modules")
+                .join("archivesrc")
+                .join(".sr")
+                .exists(),
+            "archivesrc component was not installed from the archive."
+        );
+    }
+
+    #[test]
+    /*
+     * Makes sure that a local directory that is not a component is refused by add.
+     */
+    fn test_add_invalid_local_component() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // A directory that is missing everything a component needs
+        fs::create_dir(test_dir.join("notacomponent"))
+            .expect("Failed to create notacomponent directory.");
+
+        let add_output = Command::new(&cmd_path)
+            .args(&["add", "../notacomponent"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&add_output.stderr)
+            .contains("is not a valid Sliderule component"));
+        assert!(String::from_utf8_lossy(&add_output.stderr)
+            .contains("The file package.json does not exist."));
+
+        // Nothing should have been installed
+        assert!(!test_dir
+            .join("toplevel")
+            .join("node_modules")
+            .join("notacomponent")
+            .exists());
+    }
+
+    #[test]
+    /*
+     * Tests that a failed add leaves the project the way it was before.
+     */
+    fn test_add_rollback_on_failure() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        let package_file = test_dir.join("toplevel").join("package.json");
+        let package_before = fs::read_to_string(&package_file).unwrap();
+
+        // A repository that does not exist cannot be installed
+        Command::new(&cmd_path)
+            .args(&[
+                "add",
+                "https://github.com/jmwright/this-component-does-not-exist.git",
+            ])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(fs::read_to_string(&package_file).unwrap(), package_before);
+        assert!(!test_dir
+            .join("toplevel")
+            .join("node_modules")
+            .join("this-component-does-not-exist")
+            .exists());
+    }
+
+    #[test]
+    /*
+     * Tests that a refactor that fails after changing files puts the component back the way it was.
+     */
+    fn test_refactor_rollback_on_failure() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+        let project_dir = test_dir.join("toplevel");
+
+        let output = Command::new(&cmd_path)
+            .args(&[
+                "create",
+                "-s",
+                "TestSourceLicense",
+                "-d",
+                "TestDocLicense",
+                "rollback",
+            ])
+            .current_dir(&project_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component creation finished."));
+
+        let component_dir = project_dir.join("components").join("rollback");
+        let component_before = read_tree(&component_dir);
+        let package_before = fs::read(project_dir.join("package.json")).unwrap();
+
+        // The component is made into a repository before the upload to the missing remote fails
+        Command::new(&cmd_path)
+            .args(&[
+                "refactor",
+                "-u",
+                &test_dir.join("does_not_exist.git").to_string_lossy(),
+                "rollback",
+            ])
+            .current_dir(&project_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(read_tree(&component_dir), component_before);
+        assert_eq!(
+            fs::read(project_dir.join("package.json")).unwrap(),
+            package_before
+        );
+        assert!(!project_dir.join("node_modules").join("rollback").exists());
+    }
+
+    #[test]
+    /*
+     * Tests that a remote component is cached when it is added, and can be added again from the cache in offline mode.
+     */
+    fn test_offline_add_from_cache() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+        let cache_dir = test_dir.join("cache");
+
+        // Asking for a component that has never been fetched should fail in offline mode
+        let add_output = Command::new(&cmd_path)
+            .args(&[
+                "add",
+                "--offline",
+                "https://github.com/jmwright/arduino-sr.git",
+            ])
+            .env("SLIDERULE_CACHE_DIR", &cache_dir)
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&add_output.stderr)
+            .contains("is not in the local cache and cannot be added in offline mode."));
+
+        // Adding the component with the network available should cache it
+        let add_output = Command::new(&cmd_path)
+            .args(&["add", "https://github.com/jmwright/arduino-sr.git"])
+            .env("SLIDERULE_CACHE_DIR", &cache_dir)
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&add_output.stdout).contains("Component add finished."));
+        assert!(
+            cache_dir.join("git").exists(),
+            "The remote component was not cached."
+        );
+
+        Command::new(&cmd_path)
+            .args(&["remove", "-y", "arduino-sr"])
+            .env("SLIDERULE_CACHE_DIR", &cache_dir)
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        // Now the component should come from the cache
+        let add_output = Command::new(&cmd_path)
+            .args(&[
+                "add",
+                "--offline",
+                "https://github.com/jmwright/arduino-sr.git",
+            ])
+            .env("SLIDERULE_CACHE_DIR", &cache_dir)
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&add_output.stdout).contains("Component add finished."));
+        assert!(
+            test_dir
+                .join("toplevel")
+                .join("node_modules")
+                .join("arduino-sr")
+                .exists(),
+            "arduino-sr component directory does not exist after an offline add."
+        );
+    }
+
+    #[test]
+    /*
+     * Tests copying a remote component into the project's vendor directory.
+     */
+    fn test_vendor_component() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        Command::new(&cmd_path)
+            .args(&["add", "https://github.com/jmwright/arduino-sr.git"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        let output = Command::new(&cmd_path)
+            .args(&["vendor", "arduino-sr"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component vendor finished."));
+
+        // The vendored copy should be a complete component
+        assert!(is_valid_component(
+            &test_dir.join("toplevel").join("vendor").join("arduino-sr"),
+            "arduino-sr",
+            "Unlicense",
+            "CC0-1.0",
+        ));
+
+        // The origin of the vendored component should have been recorded
+        assert!(file_contains_content(
+            &test_dir.join("toplevel").join("vendor").join("sources.txt"),
+            9999,
+            "arduino-sr https://github.com/jmwright/arduino-sr.git",
+        ));
+
+        // The project should install the vendored copy instead of going back to the repository
+        assert!(file_contains_content(
+            &test_dir.join("toplevel").join("package.json"),
+            9999,
+            "\"arduino-sr\": \"file:vendor/arduino-sr\"",
+        ));
+    }
+
+    #[test]
+    /*
+     * Tests re-syncing a vendored component with the repository it was vendored from.
+     */
+    fn test_vendor_sync() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+        let test_dir = temp_dir.join(format!("temp_{}", uuid::Uuid::new_v4()));
+        let part_dir = test_dir.join("part");
+        let project_dir = test_dir.join("board");
+        fs::create_dir_all(&part_dir).unwrap();
+        fs::create_dir_all(project_dir.join("vendor").join("part")).unwrap();
+
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .current_dir(&part_dir)
+                .output()
+                .expect("failed to execute git");
+
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        // The component is vendored at its first commit and changes upstream afterwards
+        fs::write(
+            part_dir.join("package.json"),
+            "{\n  \"name\": \"part\"\n}\n",
+        )
+        .unwrap();
+        git(&["init", "-q"]);
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "First upload"]);
+        let old_commit = git(&["rev-parse", "HEAD"]);
+
+        fs::write(part_dir.join("notes.txt"), "Added upstream").unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "Second upload"]);
+        let new_commit = git(&["rev-parse", "HEAD"]);
+
+        let url = format!("file://{}", part_dir.display());
+        fs::write(
+            project_dir.join("package.json"),
+            "{\n  \"name\": \"board\",\n  \"dependencies\": {\n    \"part\": \"file:vendor/part\"\n  }\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("vendor").join("part").join("package.json"),
+            "{\n  \"name\": \"part\"\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("vendor").join("sources.txt"),
+            format!("part {} {}\n", url, old_commit),
+        )
+        .unwrap();
+
+        let output = Command::new(&cmd_path)
+            .args(&["vendor", "--sync", "part"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains(&format!(
+            "Synced part from {} to {}.",
+            old_commit, new_commit
+        )));
+
+        // The copy is replaced with the latest version, without its repository
+        let vendor_dir = project_dir.join("vendor").join("part");
+        assert!(vendor_dir.join("notes.txt").exists());
+        assert!(!vendor_dir.join(".git").exists());
+
+        let sources = fs::read_to_string(project_dir.join("vendor").join("sources.txt")).unwrap();
+        assert!(sources.contains(&format!("part {} {}", url, new_commit)));
+    }
+
+    #[test]
+    /*
+     * Tests converting a remote component into a local component.
+     */
+    fn test_inline_component() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        Command::new(&cmd_path)
+            .args(&["add", "https://github.com/jmwright/arduino-sr.git"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        let output = Command::new(&cmd_path)
+            .args(&["inline", "arduino-sr"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component inline finished."));
+
+        // The component should now be local
+        assert!(is_valid_component(
+            &test_dir
+                .join("toplevel")
+                .join("components")
+                .join("arduino-sr"),
+            "arduino-sr",
+            "Unlicense",
+            "CC0-1.0",
+        ));
+
+        // The remote component should be gone
+        assert!(!test_dir
+            .join("toplevel")
+            .join("node_modules")
+            .join("arduino-sr")
+            .exists());
+        assert!(!file_contains_content(
+            &test_dir.join("toplevel").join("package.json"),
+            9999,
+            "arduino-sr",
+        ));
+    }
+
+    #[test]
+    /*
+     * Tests the removal of a local component.
+     */
+    fn test_remove_local() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // The remove command
+        let remove_output = match Command::new(&cmd_path)
+            .args(&["remove", "-y", "level1"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+        {
+            Ok(out) => out,
+            Err(e) => panic!(
+                "ERROR: On calling CLI with 'remove local_test' as arguments: {}",
+                e
+            ),
+        };
+
+        // stderr should be empty
+        assert!(
+            remove_output.stderr.is_empty(),
+            "stderr was not empty on local component remove"
+        );
+
+        // Check to make sure the CLI thinks it was successful in removing the local component
+        assert_eq!(
+            String::from_utf8_lossy(&remove_output.stdout).trim(),
+            "Component remove finished."
+        );
+
+        // Make sure that the local component was actually removed
+        assert!(!&test_dir
+            .join("toplevel")
+            .join("components")
+            .join("level1")
+            .exists());
+    }
+
+    #[test]
+    /*
+     * Tests renaming a local component.
+     */
+    fn test_rename_local() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        let output = Command::new(&cmd_path)
+            .args(&["rename", "level1", "renamed1"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "Component rename finished."
+        );
+
+        let component_dir = test_dir
+            .join("toplevel")
+            .join("components")
+            .join("renamed1");
+
+        // The directory and the files inside it should all use the new name
+        assert!(!test_dir
+            .join("toplevel")
+            .join("components")
+            .join("level1")
+            .exists());
+        assert!(file_contains_content(
+            &component_dir.join("package.json"),
+            9999,
+            "\"name\": \"renamed1\",",
+        ));
+        assert!(file_contains_content(
+            &component_dir.join("README.md"),
+            0,
+            "# renamed1",
+        ));
+        assert!(file_contains_content(
+            &component_dir.join("bom_data.yaml"),
+            0,
+            "# Bill of Materials Data for renamed1",
+        ));
+    }
+
+    #[test]
+    /*
+     * Tests moving a local component into another local component.
+     */
+    fn test_move_local() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Create a second local component to move the first one into
+        Command::new(&cmd_path)
+            .args(&[
+                "create",
+                "-s",
+                "NotASourceLicense",
+                "-d",
+                "NotADocLicense",
+                "holder",
+            ])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        // List the component as a part of the project so that the part moves along with it
+        let bom_file = test_dir.join("toplevel").join("bom_data.yaml");
+        fs::write(
+            &bom_file,
+            "# Bill of Materials Data for toplevel\nparts:\n  level1:\n    options:\n      - level1\n    quantity: 1\n  other:\n    options:\n      - other\n    quantity: 2\n",
+        )
+        .unwrap();
+
+        let output = Command::new(&cmd_path)
+            .args(&["move", "level1", "holder"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component move finished."));
+
+        let old_bom = fs::read_to_string(&bom_file).unwrap();
+        assert!(!old_bom.contains("level1"));
+        assert!(old_bom.contains("  other:"));
+
+        let new_bom = fs::read_to_string(
+            test_dir
+                .join("toplevel")
+                .join("components")
+                .join("holder")
+                .join("bom_data.yaml"),
+        )
+        .unwrap();
+        assert!(new_bom.contains("parts:\n"));
+        assert!(new_bom.contains("  level1:\n    options:\n      - level1\n    quantity: 1\n"));
+
+        assert!(!test_dir
+            .join("toplevel")
+            .join("components")
+            .join("level1")
+            .exists());
+        assert!(test_dir
+            .join("toplevel")
+            .join("components")
+            .join("holder")
+            .join("components")
+            .join("level1")
+            .exists());
+    }
+
+    #[test]
+    /*
+     * Tests making a new local component from a copy of an existing one.
+     */
+    fn test_copy_local() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        let output = Command::new(&cmd_path)
+            .args(&["copy", "level1", "level1_variant"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "Component copy finished."
+        );
+
+        let component_dir = test_dir
+            .join("toplevel")
+            .join("components")
+            .join("level1_variant");
+
+        // The original should be left alone
+        assert!(test_dir
+            .join("toplevel")
+            .join("components")
+            .join("level1")
+            .exists());
+
+        // The copy should use its new name and be reset to the first version
+        assert!(file_contains_content(
+            &component_dir.join("package.json"),
+            9999,
+            "\"name\": \"level1_variant\",",
+        ));
+        assert!(file_contains_content(
+            &component_dir.join("package.json"),
+            9999,
+            "\"version\": \"1.0.0\",",
+        ));
+        assert!(file_contains_content(
+            &component_dir.join("README.md"),
+            0,
+            "# level1_variant",
+        ));
+        assert!(file_contains_content(
+            &component_dir.join("bom_data.yaml"),
+            0,
+            "# Bill of Materials Data for level1_variant",
+        ));
+    }
+
+    #[test]
+    /*
+     * Tests finding and repairing problems with the structure of a component.
+     */
+    fn test_check_and_fix() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Break the structure of a local component
+        let docs_dir = test_dir
+            .join("toplevel")
+            .join("components")
+            .join("level1")
+            .join("docs");
+        fs::remove_dir_all(&docs_dir).expect("Failed to remove docs directory.");
+
+        let output = Command::new(&cmd_path)
+            .args(&["check", "--recursive"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("level1:"));
+        assert!(String::from_utf8_lossy(&output.stdout)
+            .contains("The directory docs is missing. Run check --fix to repair this."));
+
+        // Have the CLI repair the component
+        Command::new(&cmd_path)
+            .args(&["check", "--recursive", "--fix"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(docs_dir.exists(), "The docs directory was not restored.");
+    }
+
+    #[test]
+    /*
+     * Tests the report on the external tools that Sliderule needs.
+     */
+    fn test_doctor_env() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        let output = Command::new(&cmd_path)
+            .args(&["doctor", "env", "--offline"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // The tests cannot run without git and npm, but SSH may not be set up on the test machine
+        assert!(stdout.contains("[PASS] git: git version"));
+        assert!(stdout.contains("[PASS] npm: npm "));
+        assert!(stdout.contains("[SKIP] remote origin:"));
+
+        // The test project is uploaded over https, so it does not need SSH
+        assert!(stdout.contains("[SKIP] SSH agent:"));
+        assert!(stdout.contains("[SKIP] SSH key:"));
+        assert!(stdout.contains("Environment check finished"));
+    }
+
+    #[test]
+    /*
+     * Tests restoring a local component that was removed.
+     */
+    fn test_remove_and_restore() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        let component_dir = test_dir.join("toplevel").join("components").join("level1");
+
+        Command::new(&cmd_path)
+            .args(&["remove", "-y", "level1"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(!component_dir.exists());
+
+        // The removed component should be waiting in the trash
+        let list_output = Command::new(&cmd_path)
+            .args(&["trash", "list"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&list_output.stdout).contains("level1 (local, removed "));
+
+        let restore_output = Command::new(&cmd_path)
+            .args(&["restore", "level1"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(
+            String::from_utf8_lossy(&restore_output.stdout).trim(),
+            "Component restore finished."
+        );
+
+        assert!(component_dir.join(".sr").exists());
+        assert!(component_dir.join("package.json").exists());
+    }
+
+    #[test]
+    /*
+     * Tests that a dry run shows what would be removed without removing it.
+     */
+    fn test_dry_run_remove() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        let output = Command::new(&cmd_path)
+            .args(&["remove", "-y", "--dry-run", "level1"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(stdout.contains("Dry run, nothing has been changed."));
+        assert!(stdout.contains("delete directory components/level1"));
+
+        // Nothing should have been touched
+        assert!(test_dir
+            .join("toplevel")
+            .join("components")
+            .join("level1")
+            .exists());
+        assert!(!test_dir.join("toplevel").join(".sr_trash").exists());
+    }
+
+    #[test]
+    /*
+     * Tests that changes that have not been uploaded survive a download.
+     */
+    fn test_download_keeps_uncommitted_changes() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        let readme_file = test_dir.join("toplevel").join("README.md");
+        fs::write(&readme_file, "# toplevel\nChanged locally\n")
+            .expect("Failed to change README.md.");
+
+        let output = Command::new(&cmd_path)
+            .args(&["download", "all", "-y"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(stdout.contains("This component has changes that have not been uploaded:"));
+        assert!(stdout.contains("    README.md"));
+
+        // The change is set aside during the download and then put back
+        assert_eq!(
+            fs::read_to_string(&readme_file).unwrap(),
+            "# toplevel\nChanged locally\n"
+        );
+    }
+
+    #[test]
+    /*
+     * Tests merging the parts lists of a bill of materials that both sides of a merge changed.
+     */
+    fn test_resolve_bom_conflict() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+        let project_dir = test_dir.join("toplevel");
+        let bom_file = project_dir.join("bom_data.yaml");
+
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(&["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(&project_dir)
+                .output()
+                .expect("failed to execute git");
+        };
+
+        let add_part = |name: &str| {
+            let mut contents = fs::read_to_string(&bom_file).unwrap();
+            contents.push_str(&format!(
+                "  {}:\n    options:\n      - {}\n    quantity: 1\n",
+                name, name
+            ));
+            fs::write(&bom_file, contents).unwrap();
+        };
+
+        // Add a different part on each of two branches, and then merge them
+        git(&["checkout", "-b", "downloaded"]);
+        add_part("theirs_part");
+        git(&["commit", "-am", "Add a part on one branch"]);
+        git(&["checkout", "-"]);
+        add_part("ours_part");
+        git(&["commit", "-am", "Add a part on the other branch"]);
+        git(&["merge", "downloaded"]);
+
+        let list_output = Command::new(&cmd_path)
+            .args(&["conflicts", "list"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&list_output.stdout)
+            .contains("bom_data.yaml (can be merged automatically)"));
+
+        let resolve_output = Command::new(&cmd_path)
+            .args(&["conflicts", "resolve", "bom_data.yaml"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert!(
+            String::from_utf8_lossy(&resolve_output.stdout).contains("Conflict resolve finished.")
+        );
+
+        // Both parts are kept, without any conflict markers
+        let contents = fs::read_to_string(&bom_file).unwrap();
+        assert!(contents.contains("ours_part:"));
+        assert!(contents.contains("theirs_part:"));
+        assert!(!contents.contains("<<<<<<<"));
+    }
+
+    #[test]
+    /*
+     * Tests that sync refuses to run without the network.
+     */
+    fn test_sync_offline() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        let output = Command::new(&cmd_path)
+            .args(&["sync", "--offline", "-m", "Offline sync"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(output.status.code(), Some(5));
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("Components cannot be synced in offline mode."));
+    }
+
+    #[test]
+    /*
+     * Tests that sync downloads the changes made elsewhere and then uploads the changes made here.
+     */
+    fn test_sync_uploads_changes() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+        let test_dir = temp_dir.join(format!("temp_{}", uuid::Uuid::new_v4()));
+        let remote_dir = test_dir.join("board.git");
+        let board_dir = test_dir.join("board");
+        let other_dir = test_dir.join("other");
+        fs::create_dir_all(&board_dir).unwrap();
+
+        let git = |dir: &Path, args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .current_dir(dir)
+                .output()
+                .expect("failed to execute git");
+
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        // The component has been uploaded once, and is also being worked on somewhere else
+        git(
+            &test_dir,
+            &["init", "-q", "--bare", &remote_dir.to_string_lossy()],
+        );
+        fs::write(
+            board_dir.join("package.json"),
+            "{\n  \"name\": \"board\"\n}\n",
+        )
+        .unwrap();
+        fs::write(board_dir.join("notes.txt"), "First notes\n").unwrap();
+        git(&board_dir, &["init", "-q"]);
+        git(&board_dir, &["checkout", "-q", "-b", "design"]);
+        git(
+            &board_dir,
+            &["remote", "add", "origin", &remote_dir.to_string_lossy()],
+        );
+        git(&board_dir, &["add", "-A"]);
+        git(&board_dir, &["commit", "-q", "-m", "First upload"]);
+        git(&board_dir, &["push", "-q", "-u", "origin", "design"]);
+        git(
+            &test_dir,
+            &[
+                "clone",
+                "-q",
+                "-b",
+                "design",
+                &remote_dir.to_string_lossy(),
+                &other_dir.to_string_lossy(),
+            ],
+        );
+
+        // Changes are made in both places, to different files
+        fs::write(other_dir.join("parts.txt"), "Parts list\n").unwrap();
+        git(&other_dir, &["add", "-A"]);
+        git(&other_dir, &["commit", "-q", "-m", "Parts list"]);
+        git(&other_dir, &["push", "-q", "origin", "design"]);
+
+        fs::write(board_dir.join("notes.txt"), "Changed notes\n").unwrap();
+
+        let output = Command::new(&cmd_path)
+            .args(&["sync", "-m", "Synced notes"])
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .current_dir(&board_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component sync finished."));
+
+        // The downloaded changes are here, and the changes made here have been uploaded on top of them
+        assert!(board_dir.join("parts.txt").exists());
+        assert_eq!(
+            git(&remote_dir, &["log", "-1", "--format=%s", "design"]),
+            "Synced notes"
+        );
+        assert_eq!(
+            git(&remote_dir, &["show", "design:notes.txt"]),
+            "Changed notes"
+        );
+    }
+
+    #[test]
+    /*
+     * Tests that sync stops before uploading anything when the downloaded changes conflict with
+     * the changes made here.
+     */
+    fn test_sync_stops_on_conflict() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+        let test_dir = temp_dir.join(format!("temp_{}", uuid::Uuid::new_v4()));
+        let remote_dir = test_dir.join("board.git");
+        let board_dir = test_dir.join("board");
+        let other_dir = test_dir.join("other");
+        fs::create_dir_all(&board_dir).unwrap();
+
+        let git = |dir: &Path, args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .current_dir(dir)
+                .output()
+                .expect("failed to execute git");
+
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        // The component has been uploaded once, and is also being worked on somewhere else
+        git(
+            &test_dir,
+            &["init", "-q", "--bare", &remote_dir.to_string_lossy()],
+        );
+        fs::write(
+            board_dir.join("package.json"),
+            "{\n  \"name\": \"board\"\n}\n",
+        )
+        .unwrap();
+        fs::write(board_dir.join("notes.txt"), "First notes\n").unwrap();
+        git(&board_dir, &["init", "-q"]);
+        git(&board_dir, &["checkout", "-q", "-b", "design"]);
+        git(
+            &board_dir,
+            &["remote", "add", "origin", &remote_dir.to_string_lossy()],
+        );
+        git(&board_dir, &["add", "-A"]);
+        git(&board_dir, &["commit", "-q", "-m", "First upload"]);
+        git(&board_dir, &["push", "-q", "-u", "origin", "design"]);
+        git(
+            &test_dir,
+            &[
+                "clone",
+                "-q",
+                "-b",
+                "design",
+                &remote_dir.to_string_lossy(),
+                &other_dir.to_string_lossy(),
+            ],
+        );
+
+        // Both places change the same lines of the same file
+        fs::write(other_dir.join("notes.txt"), "Notes from elsewhere\n").unwrap();
+        git(&other_dir, &["add", "-A"]);
+        git(&other_dir, &["commit", "-q", "-m", "Notes from elsewhere"]);
+        git(&other_dir, &["push", "-q", "origin", "design"]);
+        let remote_commit = git(&other_dir, &["rev-parse", "HEAD"]);
+
+        fs::write(board_dir.join("notes.txt"), "Notes from here\n").unwrap();
+
+        let output = Command::new(&cmd_path)
+            .args(&["sync", "-m", "Synced notes"])
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .current_dir(&board_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(output.status.code(), Some(8));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("notes.txt"));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Component sync stopped"));
+
+        // Nothing was uploaded on top of the conflict
+        assert_eq!(git(&remote_dir, &["rev-parse", "design"]), remote_commit);
+    }
+
+    #[test]
+    /*
+     * Tests linking a remote component to a checkout of it, and then unlinking it again.
+     */
+    fn test_link_and_unlink() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        Command::new(&cmd_path)
+            .args(&["add", "https://github.com/jmwright/arduino-sr.git"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        // Download a writable checkout of the component next to the project
+        Command::new(&cmd_path)
+            .args(&["download", "https://github.com/jmwright/arduino-sr.git"])
+            .current_dir(&test_dir)
+            .output()
+            .expect("failed to execute process");
+
+        let installed_dir = test_dir
+            .join("toplevel")
+            .join("node_modules")
+            .join("arduino-sr");
+
+        let output = Command::new(&cmd_path)
+            .args(&["link", "arduino-sr", "../arduino-sr"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "Component link finished."
+        );
+        assert!(fs::symlink_metadata(&installed_dir)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+
+        let output = Command::new(&cmd_path)
+            .args(&["unlink", "arduino-sr"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component unlink finished."));
+
+        // The component is installed from its repository again
+        assert!(!fs::symlink_metadata(&installed_dir)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert!(installed_dir.join("package.json").exists());
+    }
+
+    #[test]
+    /*
+     * Tests creating a branch, merging it back into the main branch and deleting it.
+     */
+    fn test_branch_create_merge_delete() {
         let cmd_path = env::current_dir()
             .unwrap()
             .join("target")
@@ -90,29 +1684,62 @@ mod management {
 
         // Set up our temporary project directory for testing
         let test_dir = set_up(&temp_dir, "toplevel");
+        let project_dir = test_dir.join("toplevel");
 
-        // Try to download the component
-        let output = Command::new(cmd_path)
-            .args(&["download", "https://github.com/jmwright/blink_firmware.git"])
-            .current_dir(&test_dir)
+        let run = |args: &[&str]| {
+            let output = Command::new(&cmd_path)
+                .args(args)
+                .current_dir(&project_dir)
+                .output()
+                .expect("failed to execute process");
+
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+
+        let original = Command::new("git")
+            .args(&["rev-parse", "--abbrev-ref", "HEAD"])
+            .current_dir(&project_dir)
             .output()
-            .expect("failed to execute process");
+            .expect("failed to execute git");
+        let original = String::from_utf8_lossy(&original.stdout).trim().to_string();
+
+        assert!(run(&["branch", "create", "explore"]).contains("Branch create finished"));
+        assert!(run(&["status"]).contains("On branch explore"));
+        assert!(run(&["branch", "list"]).contains("* explore"));
+
+        // Make a design change on the new branch
+        fs::write(
+            project_dir.join("source").join("alternative.txt"),
+            "option B",
+        )
+        .unwrap();
+        for args in &[
+            &["add", "-A"][..],
+            &["commit", "-m", "Add an alternative"][..],
+        ] {
+            Command::new("git")
+                .args(&["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(*args)
+                .current_dir(&project_dir)
+                .output()
+                .expect("failed to execute git");
+        }
 
-        assert!(String::from_utf8_lossy(&output.stdout).contains("Component download finished."));
+        assert!(run(&["branch", "switch", &original]).contains("Branch switch finished"));
+        assert!(!project_dir.join("source").join("alternative.txt").exists());
 
-        assert!(is_valid_component(
-            &test_dir.join("blink_firmware"),
-            "blink_firmware",
-            "Unlicense",
-            "CC0-1.0",
-        ));
+        assert!(run(&["branch", "merge", "explore"]).contains("Branch merge finished"));
+        assert!(project_dir.join("source").join("alternative.txt").exists());
+
+        assert!(run(&["branch", "delete", "explore"]).contains("Branch delete finished."));
+        assert!(!run(&["branch", "list"]).contains("explore"));
     }
 
     #[test]
     /*
-     * Tests the ability to download remote changes for a component
+     * Tests putting the files of a component back the way they were at an earlier upload.
      */
-    fn test_download_component_changes() {
+    fn test_revert_to_previous_upload() {
         let cmd_path = env::current_dir()
             .unwrap()
             .join("target")
@@ -123,22 +1750,54 @@ mod management {
 
         // Set up our temporary project directory for testing
         let test_dir = set_up(&temp_dir, "toplevel");
+        let project_dir = test_dir.join("toplevel");
+        let bom_file = project_dir.join("bom_data.yaml");
 
-        // Try to download the component's remote changes
-        let output = Command::new(cmd_path)
-            .args(&["download", "all"])
-            .current_dir(&test_dir.join("toplevel"))
+        let original = fs::read_to_string(&bom_file).unwrap();
+
+        // Record a change to the bill of materials that turns out to be wrong
+        fs::write(
+            &bom_file,
+            format!("{}  wrong_part:\n    quantity: 1\n", original),
+        )
+        .unwrap();
+        for args in &[
+            &["add", "-A"][..],
+            &["commit", "-m", "Add the wrong part"][..],
+        ] {
+            Command::new("git")
+                .args(&["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(*args)
+                .current_dir(&project_dir)
+                .output()
+                .expect("failed to execute git");
+        }
+
+        let output = Command::new(&cmd_path)
+            .args(&["revert"])
+            .current_dir(&project_dir)
             .output()
             .expect("failed to execute process");
 
-        assert!(String::from_utf8_lossy(&output.stdout).contains("Component download finished."));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Reverted bom_data.yaml"));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component revert finished."));
+        assert_eq!(fs::read_to_string(&bom_file).unwrap(), original);
+
+        // The history is kept, with the revert waiting to be uploaded as a new change
+        let output = Command::new(&cmd_path)
+            .args(&["changes", "history"])
+            .current_dir(&project_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Add the wrong part"));
     }
 
+    #[test]
     /*
-     * Tests the ability to download changes to the remote components housed within a component/project
+     * Tests checking out a tagged release of a component into a directory of its own.
      */
-    #[test]
-    fn test_download_dependencies() {
+    fn test_checkout_release() {
         let cmd_path = env::current_dir()
             .unwrap()
             .join("target")
@@ -149,23 +1808,63 @@ mod management {
 
         // Set up our temporary project directory for testing
         let test_dir = set_up(&temp_dir, "toplevel");
+        let project_dir = test_dir.join("toplevel");
+        let readme_file = project_dir.join("README.md");
+
+        let released = fs::read_to_string(&readme_file).unwrap();
+
+        // Tag the release, and then keep working on the design
+        for args in &[
+            &["add", "-A"][..],
+            &["commit", "--allow-empty", "-m", "Prepare the release"][..],
+            &["tag", "v1.0.0"][..],
+        ] {
+            Command::new("git")
+                .args(&["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(*args)
+                .current_dir(&project_dir)
+                .output()
+                .expect("failed to execute git");
+        }
 
-        // Try to download the component's remote changes
-        let output = Command::new(cmd_path)
-            .args(&["download", "dependencies"])
-            .current_dir(&test_dir.join("toplevel"))
+        fs::write(&readme_file, "# toplevel\n\nA later revision.\n").unwrap();
+        for args in &[&["add", "-A"][..], &["commit", "-m", "Keep working"][..]] {
+            Command::new("git")
+                .args(&["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(*args)
+                .current_dir(&project_dir)
+                .output()
+                .expect("failed to execute git");
+        }
+
+        let output = Command::new(&cmd_path)
+            .args(&["checkout", "1.0.0", "--into", "../release"])
+            .current_dir(&project_dir)
             .output()
             .expect("failed to execute process");
 
-        assert!(String::from_utf8_lossy(&output.stdout)
-            .contains("Component download of dependencies only finished."));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component checkout finished"));
+        assert_eq!(
+            fs::read_to_string(test_dir.join("release").join("README.md")).unwrap(),
+            released
+        );
+
+        // The component itself is left on its latest revision
+        assert!(fs::read_to_string(&readme_file)
+            .unwrap()
+            .contains("A later revision."));
     }
 
     #[test]
     /*
-     * Tests the addition and removal of a remote component.
+     * Tests creating the repository on a Gitea server before the first upload.
      */
-    fn test_add_remove_component() {
+    fn test_create_remote_on_server() {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpListener;
+        use std::sync::{Arc, Mutex};
+        use std::thread;
+
         let cmd_path = env::current_dir()
             .unwrap()
             .join("target")
@@ -173,67 +1872,294 @@ mod management {
             .join("sliderule-cli");
 
         let temp_dir = env::temp_dir();
+        let test_dir = temp_dir.join(format!("temp_{}", uuid::Uuid::new_v4()));
+        let config_dir = test_dir.join("config");
+        let project_dir = test_dir.join("board");
+        fs::create_dir_all(config_dir.join("sliderule")).unwrap();
+        fs::create_dir_all(&project_dir).unwrap();
+
+        // Stand in for a Gitea server, recording each request that is made to it
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+
+                let mut length = 0;
+                let mut authorized = false;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if header.to_lowercase().starts_with("content-length:") {
+                        length = header[15..].trim().parse().unwrap();
+                    }
+                    if header.trim() == "Authorization: token secret-token" {
+                        authorized = true;
+                    }
+                }
+
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                let request_line = request_line.trim().to_string();
+                let (status, response) = if !authorized {
+                    ("401 Unauthorized", "{}")
+                } else if request_line.starts_with("GET /api/v1/user ") {
+                    ("200 OK", "{\"id\": 1, \"login\": \"alice\"}")
+                } else if request_line.starts_with("POST /api/v1/user/repos ") {
+                    ("201 Created", "{\"id\": 2}")
+                } else {
+                    ("404 Not Found", "{}")
+                };
+
+                recorded.lock().unwrap().push(format!(
+                    "{} {}",
+                    request_line,
+                    String::from_utf8_lossy(&body)
+                ));
+
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    response.len(),
+                    response
+                );
+            }
+        });
+
+        fs::write(
+            config_dir.join("sliderule").join("servers"),
+            format!("http://127.0.0.1:{} gitea secret-token\n", port),
+        )
+        .unwrap();
 
-        // Set up our temporary project directory for testing
-        let test_dir = set_up(&temp_dir, "toplevel");
-
-        // The add command
-        let add_output = Command::new(&cmd_path)
-            .args(&["add", "https://github.com/jmwright/arduino-sr.git"])
-            .current_dir(&test_dir.join("toplevel"))
+        let output = Command::new(&cmd_path)
+            .args(&[
+                "upload",
+                "-m",
+                "First upload",
+                "-u",
+                &format!("http://127.0.0.1:{}/alice/board.git", port),
+                "--create-remote",
+            ])
+            .env("XDG_CONFIG_HOME", &config_dir)
+            .current_dir(&project_dir)
             .output()
             .expect("failed to execute process");
 
-        assert!(String::from_utf8_lossy(&add_output.stdout).contains("Component add finished."));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Repository"));
 
-        // Make sure that the newly installed remote component is actually there
-        assert!(
-            &test_dir
-                .join("toplevel")
-                .join("node_modules")
-                .join("arduino-sr")
-                .exists(),
-            "arduino-sr component directory does not exist."
+        let requests = requests.lock().unwrap();
+        assert!(requests
+            .iter()
+            .any(|request| request.starts_with("GET /api/v1/repos/alice/board ")));
+        assert!(requests
+            .iter()
+            .any(|request| request.starts_with("POST /api/v1/user/repos ")
+                && request.contains("\"name\": \"board\"")));
+    }
+
+    #[test]
+    /*
+     * Tests uploading a checked out remote component before the component that uses it, and
+     * pointing the lock file of that component at the commit that was just uploaded.
+     */
+    fn test_upload_recursive() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+        let test_dir = temp_dir.join(format!("temp_{}", uuid::Uuid::new_v4()));
+        let part_remote = test_dir.join("part.git");
+        let board_remote = test_dir.join("board.git");
+        let board_dir = test_dir.join("board");
+        let part_dir = board_dir.join("node_modules").join("part");
+        fs::create_dir_all(&board_dir).unwrap();
+
+        let git = |dir: &Path, args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .current_dir(dir)
+                .output()
+                .expect("failed to execute git");
+
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        git(
+            &test_dir,
+            &["init", "-q", "--bare", &part_remote.to_string_lossy()],
+        );
+        git(
+            &test_dir,
+            &["init", "-q", "--bare", &board_remote.to_string_lossy()],
         );
 
-        // Make sure the component is valid
-        assert!(is_valid_component(
-            &test_dir
-                .join("toplevel")
-                .join("node_modules")
-                .join("arduino-sr"),
-            "arduino-sr",
-            "Unlicense",
-            "CC0-1.0",
-        ));
+        // The remote component is checked out for editing inside of the component that uses it
+        fs::create_dir_all(&part_dir).unwrap();
+        fs::write(
+            part_dir.join("package.json"),
+            "{\n  \"name\": \"part\"\n}\n",
+        )
+        .unwrap();
+        git(&part_dir, &["init", "-q"]);
+        git(&part_dir, &["checkout", "-q", "-b", "design"]);
+        git(
+            &part_dir,
+            &["remote", "add", "origin", &part_remote.to_string_lossy()],
+        );
+        git(&part_dir, &["add", "-A"]);
+        git(&part_dir, &["commit", "-q", "-m", "First upload"]);
+        git(&part_dir, &["push", "-q", "-u", "origin", "design"]);
+        let old_commit = git(&part_dir, &["rev-parse", "HEAD"]);
+
+        let part_url = format!("git+file://{}", part_remote.display());
+        fs::write(
+            board_dir.join("package.json"),
+            format!(
+                "{{\n  \"name\": \"board\",\n  \"dependencies\": {{\n    \"part\": \"{}\"\n  }}\n}}\n",
+                part_url
+            ),
+        )
+        .unwrap();
+        fs::write(
+            board_dir.join("package-lock.json"),
+            format!(
+                "{{\n  \"name\": \"board\",\n  \"lockfileVersion\": 1,\n  \"dependencies\": {{\n    \"part\": {{\n      \"version\": \"{}#{}\",\n      \"from\": \"{}\"\n    }}\n  }}\n}}\n",
+                part_url, old_commit, part_url
+            ),
+        )
+        .unwrap();
+        fs::write(board_dir.join(".gitignore"), "node_modules\n").unwrap();
+        git(&board_dir, &["init", "-q"]);
+        git(&board_dir, &["checkout", "-q", "-b", "design"]);
+        git(
+            &board_dir,
+            &["remote", "add", "origin", &board_remote.to_string_lossy()],
+        );
+        git(&board_dir, &["add", "-A"]);
+        git(&board_dir, &["commit", "-q", "-m", "First upload"]);
+        git(&board_dir, &["push", "-q", "-u", "origin", "design"]);
 
-        // The remove command
-        let remove_output = Command::new(&cmd_path)
-            .args(&["remove", "-y", "arduino-sr"])
-            .current_dir(&test_dir.join("toplevel"))
+        fs::write(part_dir.join("notes.txt"), "Changed in place").unwrap();
+
+        let output = Command::new(&cmd_path)
+            .args(&["upload", "--recursive", "-m", "Recursive upload"])
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .current_dir(&board_dir)
             .output()
             .expect("failed to execute process");
 
-        assert!(
-            String::from_utf8_lossy(&remove_output.stdout).contains("Component remove finished.")
-        );
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+        // The remote component is uploaded before the component that uses it
+        let child_index = stdout
+            .find("Component node_modules/part upload finished.")
+            .expect("the remote component was not uploaded");
+        let parent_index = stdout
+            .find("\nComponent upload finished.")
+            .expect("the component was not uploaded");
+        assert!(child_index < parent_index);
+
+        let new_commit = git(&part_remote, &["rev-parse", "design"]);
+        assert_ne!(new_commit, old_commit);
+
+        // The upload of the component carries the lock file pointing at the new commit
+        let lock = git(&board_remote, &["show", "design:package-lock.json"]);
+        assert!(lock.contains(&format!("{}#{}", part_url, new_commit)));
+        assert!(!lock.contains(&old_commit));
+    }
+
+    #[test]
+    /*
+     * Tests uploading to a remote other than origin without changing where uploads go by default.
+     */
+    fn test_upload_to_named_remote() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+        let project_dir = test_dir.join("toplevel");
+        let mirror_dir = test_dir.join("mirror.git");
+
+        Command::new("git")
+            .args(&["init", "--bare", &mirror_dir.to_string_lossy()])
+            .output()
+            .expect("failed to execute git");
+
+        let run = |args: &[&str]| {
+            let output = Command::new(&cmd_path)
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .current_dir(&project_dir)
+                .output()
+                .expect("failed to execute process");
+
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
 
-        // Make sure that the newly removed remote component was actually removed
         assert!(
-            !&test_dir
-                .join("toplevel")
-                .join("node_modules")
-                .join("arduino-sr")
-                .exists(),
-            "arduino-sr component directory does not exist."
+            run(&["remote", "add", "mirror", &mirror_dir.to_string_lossy()])
+                .contains("Remote add finished.")
         );
+        assert!(run(&["remote", "list"]).contains(&format!("mirror {}", mirror_dir.display())));
+
+        fs::write(
+            project_dir.join("source").join("release.txt"),
+            "for the public",
+        )
+        .unwrap();
+
+        assert!(run(&["upload", "-m", "Open release", "--remote", "mirror"])
+            .contains("Component upload finished."));
+
+        // Only the mirror has the upload
+        let log = Command::new("git")
+            .args(&["log", "--format=%s"])
+            .current_dir(&mirror_dir)
+            .output()
+            .expect("failed to execute git");
+        assert!(String::from_utf8_lossy(&log.stdout).contains("Open release"));
+
+        assert!(run(&["remote", "remove", "mirror"]).contains("Remote remove finished."));
+        assert!(!run(&["remote", "list"]).contains("mirror"));
     }
 
     #[test]
     /*
-     * Tests the removal of a local component.
+     * Tests pushing a project to a mirror and recording the mirror as a remote.
      */
-    fn test_remove_local() {
+    fn test_mirror_project() {
         let cmd_path = env::current_dir()
             .unwrap()
             .join("target")
@@ -244,38 +2170,156 @@ mod management {
 
         // Set up our temporary project directory for testing
         let test_dir = set_up(&temp_dir, "toplevel");
+        let host_dir = test_dir.join("host");
 
-        // The remove command
-        let remove_output = match Command::new(&cmd_path)
-            .args(&["remove", "-y", "level1"])
+        let output = Command::new(&cmd_path)
+            .args(&["mirror", &host_dir.to_string_lossy(), "--create-remote"])
             .current_dir(&test_dir.join("toplevel"))
             .output()
-        {
-            Ok(out) => out,
-            Err(e) => panic!(
-                "ERROR: On calling CLI with 'remove local_test' as arguments: {}",
-                e
-            ),
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Project mirror finished."));
+
+        // The project is pushed to the mirror and recorded as a remote named mirror
+        assert!(host_dir.join("toplevel.git").join("HEAD").exists());
+
+        let output = Command::new(&cmd_path)
+            .args(&["remote", "list"])
+            .current_dir(&test_dir.join("toplevel"))
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("mirror "));
+    }
+
+    #[test]
+    /*
+     * Tests that only the URLs of the mirrored components are rewritten, even when one of them
+     * starts with the URL of another.
+     */
+    fn test_mirror_rewrite_urls() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+        let test_dir = temp_dir.join(format!("temp_{}", uuid::Uuid::new_v4()));
+        let source_dir = test_dir.join("source");
+        let host_dir = test_dir.join("host");
+        let project_dir = test_dir.join("board");
+        fs::create_dir_all(&project_dir).unwrap();
+
+        let git = |dir: &Path, args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .current_dir(dir)
+                .output()
+                .expect("failed to execute git");
         };
 
-        // stderr should be empty
-        assert!(
-            remove_output.stderr.is_empty(),
-            "stderr was not empty on local component remove"
-        );
+        // Two remote components where the URL of one starts with the URL of the other
+        for name in &["part", "partx"] {
+            let dir = source_dir.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("package.json"),
+                format!("{{\n  \"name\": \"{}\"\n}}\n", name),
+            )
+            .unwrap();
+
+            git(&dir, &["init", "-q"]);
+            git(&dir, &["add", "-A"]);
+            git(&dir, &["commit", "-q", "-m", "First upload"]);
+        }
+
+        let part_url = format!("file://{}", source_dir.join("part").display());
+        fs::write(
+            project_dir.join("package.json"),
+            format!(
+                "{{\n  \"name\": \"board\",\n  \"dependencies\": {{\n    \"part\": \"git+{}\",\n    \"partx\": \"git+{}x#abc123\"\n  }}\n}}\n",
+                part_url, part_url
+            ),
+        )
+        .unwrap();
+
+        git(&project_dir, &["init", "-q"]);
+        git(&project_dir, &["add", "-A"]);
+        git(&project_dir, &["commit", "-q", "-m", "First upload"]);
+
+        let output = Command::new(&cmd_path)
+            .args(&[
+                "mirror",
+                &host_dir.to_string_lossy(),
+                "--create-remote",
+                "--rewrite-urls",
+                "--all",
+            ])
+            .current_dir(&project_dir)
+            .output()
+            .expect("failed to execute process");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Project mirror finished."));
+
+        let contents = fs::read_to_string(project_dir.join("package.json")).unwrap();
+
+        assert!(contents.contains(&format!(
+            "\"part\": \"git+file://{}\"",
+            host_dir.join("part.git").display()
+        )));
+        assert!(contents.contains(&format!(
+            "\"partx\": \"git+file://{}#abc123\"",
+            host_dir.join("partx.git").display()
+        )));
+    }
+
+    #[test]
+    /*
+     * Tests that new components keep their large CAD and fabrication files in Git LFS.
+     */
+    fn test_create_writes_lfs_attributes() {
+        let cmd_path = env::current_dir()
+            .unwrap()
+            .join("target")
+            .join(cargo_mode())
+            .join("sliderule-cli");
+
+        let temp_dir = env::temp_dir();
+
+        // Set up our temporary project directory for testing
+        let test_dir = set_up(&temp_dir, "toplevel");
+
+        // Keep any patterns file of the user running the tests out of the way
+        let output = Command::new(&cmd_path)
+            .args(&[
+                "create",
+                "-s",
+                "TestSourceLicense",
+                "-d",
+                "TestDocLicense",
+                "lfs_board",
+            ])
+            .env("XDG_CONFIG_HOME", test_dir.join("config"))
+            .current_dir(&test_dir)
+            .output()
+            .expect("failed to execute process");
 
-        // Check to make sure the CLI thinks it was successful in removing the local component
         assert_eq!(
-            String::from_utf8_lossy(&remove_output.stdout).trim(),
-            "Component remove finished."
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "Component creation finished."
         );
 
-        // Make sure that the local component was actually removed
-        assert!(!&test_dir
-            .join("toplevel")
-            .join("components")
-            .join("level1")
-            .exists());
+        let attributes = fs::read_to_string(test_dir.join("lfs_board").join(".gitattributes"))
+            .expect("Could not read .gitattributes");
+
+        assert!(attributes.contains("*.step filter=lfs diff=lfs merge=lfs -text"));
+        assert!(attributes.contains("*.FCStd filter=lfs diff=lfs merge=lfs -text"));
+        assert!(attributes.contains("docs/**/*.png filter=lfs diff=lfs merge=lfs -text"));
     }
 
     #[test]
@@ -717,6 +2761,30 @@ mod management {
         temp_dir.join(test_dir_name)
     }
 
+    /*
+     * Helper function that reads every file in a directory, so that two states of it can be compared.
+     */
+    fn read_tree(dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut files = Vec::new();
+
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+            .expect("ERROR: Cannot read the directory.")
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                files.push((path.clone(), Vec::new()));
+                files.extend(read_tree(&path));
+            } else {
+                files.push((path.clone(), fs::read(&path).unwrap()));
+            }
+        }
+
+        files
+    }
+
     /*
      * Helper function that checks to make sure that given text is present in the files.
      */